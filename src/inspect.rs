@@ -0,0 +1,272 @@
+use super::USAGE;
+use serde_json::{json, Value};
+use storage::{Block, Storage, Superblock, NEONDB_FILE_MARK};
+
+use std::path::Path;
+
+// Jumlah byte yang ditampilkan di tiap baris hex dump
+const DUMP_WIDTH: usize = 16;
+
+// Menampilkan isi dari volume yang diberikan, tanpa mengubah file volume
+// maupun log-nya sama sekali. Header dibaca langsung dari file volume
+// (beserta keterangan jika log belum diterapkan), sedangkan subcommand
+// lainnya melakukan mounting secara read-only, sehingga sisa transaksi di
+// log (jika ada) ikut terlihat namun hanya diterapkan di memori.
+pub fn inspect(args: &[String]) -> Result<i32, String> {
+    let is_json = args.iter().any(|arg| arg == "--json");
+    let args = args
+        .iter()
+        .filter(|arg| *arg != "--json")
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+
+    let (command, path, address) = match args[..] {
+        [command, path] => (command, Path::new(path), None),
+        ["dump", path, address] => ("dump", Path::new(path), Some(parse_address(address)?)),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let output = match (command, address) {
+        ("header", _) => {
+            let superblock = storage::volume_info(path).map_err(|err| err.to_string())?;
+
+            header(&superblock, storage::has_unrecovered_log(path))
+        }
+        ("blocks", _) => blocks(&mut mount(path)?)?,
+        ("stats", _) => stats(&mount(path)?)?,
+        ("dump", Some(address)) => dump(&mut mount(path)?, address)?,
+        _ => return Err(USAGE.to_string()),
+    };
+
+    output.print(is_json);
+
+    Ok(0)
+}
+
+fn mount(path: &Path) -> Result<Storage, String> {
+    let mut s = Storage::new();
+    s.mount_read_only(path).map_err(|err| err.to_string())?;
+
+    Ok(s)
+}
+
+// Hasil dari tiap subcommand, dalam dua bentuk sekaligus
+pub struct Output {
+    pub text: String,
+    pub json: Value,
+}
+
+impl Output {
+    pub fn print(&self, is_json: bool) {
+        if is_json {
+            println!("{}", serde_json::to_string_pretty(&self.json).unwrap());
+        } else {
+            print!("{}", self.text);
+        }
+    }
+}
+
+pub fn header(superblock: &Superblock, is_log_unrecovered: bool) -> Output {
+    let uuid = format_uuid(&superblock.uuid);
+    let block_map = superblock.block_map.map(|map| map.address);
+
+    let mut text = String::new();
+
+    text += &format!("mark:               {}\n", NEONDB_FILE_MARK);
+    text += &format!("version:            {}\n", superblock.version);
+    text += &format!("allocator:          {:?}\n", superblock.allocator);
+    text += &format!("size:               {}\n", superblock.size);
+    text += &format!("uuid:               {}\n", uuid);
+    text += &format!("page size:          {}\n", superblock.page_size);
+    text += &format!("compat features:    {:#x}\n", superblock.compat_features);
+    text += &format!("incompat features:  {:#x}\n", superblock.incompat_features);
+    text += &format!("ro-compat features: {:#x}\n", superblock.ro_compat_features);
+    text += &format!("checksums:          {:?}\n", superblock.checksums);
+    text += &format!("block map:          {}\n", format_optional(block_map));
+    text += &format!(
+        "slab root:          {}\n",
+        format_optional(superblock.slab_root)
+    );
+    text += &format!(
+        "unrecovered log:    {}\n",
+        if is_log_unrecovered { "yes" } else { "no" }
+    );
+    text += &format!("roots:              {}\n", superblock.roots.len());
+
+    for (name, address) in &superblock.roots {
+        text += &format!("  {} -> {}\n", name, address);
+    }
+
+    let json = json!({
+        "mark": NEONDB_FILE_MARK,
+        "version": superblock.version,
+        "allocator": format!("{:?}", superblock.allocator),
+        "size": superblock.size,
+        "uuid": uuid,
+        "page_size": superblock.page_size,
+        "compat_features": superblock.compat_features,
+        "incompat_features": superblock.incompat_features,
+        "ro_compat_features": superblock.ro_compat_features,
+        "checksums": format!("{:?}", superblock.checksums),
+        "block_map": block_map,
+        "slab_root": superblock.slab_root,
+        "unrecovered_log": is_log_unrecovered,
+        "roots": superblock.roots,
+    });
+
+    Output { text, json }
+}
+
+// Blok yang sedang digunakan ditampilkan dengan alamat dan ukuran isinya
+// (sama seperti `Storage::blocks`), sedangkan ruang kosong maupun blok
+// milik allocator (head dan peta blok) ditampilkan dengan alamat dan
+// ukuran sebenarnya.
+pub fn blocks(s: &mut Storage) -> Result<Output, String> {
+    let free = s.free_blocks().map_err(|err| err.to_string())?;
+    let reserved = s.reserved_blocks().map_err(|err| err.to_string())?;
+    let used = s.blocks().map_err(|err| err.to_string())?;
+
+    let mut blocks = used
+        .iter()
+        .map(|block| (block, "used"))
+        .chain(free.iter().map(|block| (block, "free")))
+        .chain(reserved.iter().map(|block| (block, "reserved")))
+        .collect::<Vec<(&Block, &str)>>();
+
+    blocks.sort_by_key(|(block, _)| block.address);
+
+    let mut text = format!("{:>12} {:>12}  state\n", "address", "size");
+
+    for (block, state) in &blocks {
+        text += &format!("{:>12} {:>12}  {}\n", block.address, block.size, state);
+    }
+
+    let json = blocks
+        .iter()
+        .map(|(block, state)| {
+            json!({
+                "address": block.address,
+                "size": block.size,
+                "state": state,
+            })
+        })
+        .collect();
+
+    Ok(Output { text, json })
+}
+
+pub fn stats(s: &Storage) -> Result<Output, String> {
+    let stats = s.stats().map_err(|err| err.to_string())?;
+    let total = stats.used_bytes + stats.free_bytes;
+
+    let free_ratio = if total == 0 {
+        0.0
+    } else {
+        stats.free_bytes as f64 / total as f64
+    };
+
+    let mut text = String::new();
+
+    text += &format!("used blocks:        {}\n", stats.used_blocks);
+    text += &format!("used bytes:         {}\n", stats.used_bytes);
+    text += &format!("free blocks:        {}\n", stats.free_blocks);
+    text += &format!("free bytes:         {}\n", stats.free_bytes);
+    text += &format!("free space:         {:.2}%\n", free_ratio * 100.0);
+    text += &format!("largest free block: {}\n", stats.largest_free_block);
+    text += &format!(
+        "fragmentation:      {:.2}%\n",
+        stats.fragmentation() * 100.0
+    );
+
+    let json = json!({
+        "used_blocks": stats.used_blocks,
+        "used_bytes": stats.used_bytes,
+        "free_blocks": stats.free_blocks,
+        "free_bytes": stats.free_bytes,
+        "free_ratio": free_ratio,
+        "largest_free_block": stats.largest_free_block,
+        "fragmentation": stats.fragmentation(),
+    });
+
+    Ok(Output { text, json })
+}
+
+// Menampilkan seluruh isi dari blok yang memuat alamat yang diberikan,
+// mulai dari alamat awal blok tersebut.
+pub fn dump(s: &mut Storage, address: u64) -> Result<Output, String> {
+    let block = s
+        .blocks()
+        .map_err(|err| err.to_string())?
+        .iter()
+        .find(|block| (block.address..block.address + block.size).contains(&address))
+        .map(|block| (block.address, block.size));
+
+    let (address, size) = block.ok_or(format!("no block found at address {}", address))?;
+
+    let mut bytes = vec![0u8; size as usize];
+    s.read(address, &mut bytes).map_err(|err| err.to_string())?;
+
+    let mut text = String::new();
+
+    for (i, line) in bytes.chunks(DUMP_WIDTH).enumerate() {
+        let hex = line
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect::<Vec<String>>()
+            .join(" ");
+
+        let ascii = line
+            .iter()
+            .map(|byte| match byte {
+                0x20..=0x7e => *byte as char,
+                _ => '.',
+            })
+            .collect::<String>();
+
+        text += &format!(
+            "{:012x}  {:<width$}  |{}|\n",
+            address + (i * DUMP_WIDTH) as u64,
+            hex,
+            ascii,
+            width = DUMP_WIDTH * 3 - 1,
+        );
+    }
+
+    let json = json!({
+        "address": address,
+        "size": size,
+        "payload": bytes.iter().map(|byte| format!("{:02x}", byte)).collect::<String>(),
+    });
+
+    Ok(Output { text, json })
+}
+
+// Alamat dapat ditulis dalam desimal maupun heksadesimal (diawali "0x")
+pub fn parse_address(address: &str) -> Result<u64, String> {
+    let res = match address.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => address.parse(),
+    };
+
+    res.map_err(|_| format!("invalid address: {}", address))
+}
+
+fn format_uuid(uuid: &[u8; 16]) -> String {
+    let hex = uuid
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect::<String>();
+
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
+
+fn format_optional(value: Option<u64>) -> String {
+    value.map_or("-".to_string(), |value| value.to_string())
+}
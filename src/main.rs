@@ -1,3 +1,61 @@
+mod inspect;
+mod volume;
+
+#[cfg(test)]
+mod tests;
+
+use std::env;
+use std::path::Path;
+use std::process;
+
+const USAGE: &str =
+    "usage: neondb create <volume> [--size <size>] [--allocator <rss|buddy>] [--force] [--json]
+       neondb info <volume> [--json]
+       neondb check <volume> [--repair]
+       neondb inspect <header|blocks|stats|dump> <volume> [<address>] [--json]";
+
 fn main() {
-    println!("Hello, world!");
+    let args = env::args().skip(1).collect::<Vec<String>>();
+
+    let res = match args.first().map(String::as_str) {
+        Some("create") => volume::create(&args[1..]),
+        Some("info") => volume::info(&args[1..]),
+        Some("check") => check(&args[1..]),
+        Some("inspect") => inspect::inspect(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+
+    match res {
+        Ok(code) => process::exit(code),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    }
+}
+
+// Memeriksa (sekaligus memperbaiki, jika diminta) volume yang diberikan.
+// Exit code bernilai 1 jika kerusakan ditemukan namun tidak diperbaiki.
+fn check(args: &[String]) -> Result<i32, String> {
+    let (path, is_repair) = match args {
+        [path] => (path, false),
+        [path, flag] if flag == "--repair" => (path, true),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let path = Path::new(path);
+    let res = if is_repair {
+        storage::repair(path)
+    } else {
+        storage::check(path)
+    };
+
+    let report = res.map_err(|err| err.to_string())?;
+    print!("{}", report);
+
+    Ok(if report.is_clean() || report.is_repaired {
+        0
+    } else {
+        1
+    })
 }
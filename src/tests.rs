@@ -0,0 +1,4 @@
+mod util;
+
+mod test_inspect;
+mod test_volume;
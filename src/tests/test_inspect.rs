@@ -0,0 +1,138 @@
+use super::util;
+use crate::inspect;
+use storage::{Storage, NEONDB_FILE_ALLOCATABLE_START, NEONDB_FILE_DEFAULT_SIZE};
+
+use std::path::Path;
+
+// Volume berisi satu blok dengan isi yang diberikan, beserta alamat dari
+// blok tersebut
+fn volume_with_block(path: &Path, bytes: &[u8]) -> u64 {
+    let mut s = Storage::new();
+    s.mount_new(path, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    let address = s.alloc(bytes.len()).unwrap();
+    s.write(address, bytes).unwrap();
+    s.unmount().unwrap();
+
+    address
+}
+
+fn mount_read_only(path: &Path) -> Storage {
+    let mut s = Storage::new();
+    s.mount_read_only(path).unwrap();
+
+    s
+}
+
+#[test]
+fn parse_decimal_and_hex_address() {
+    assert!(inspect::parse_address("4096") == Ok(4096));
+    assert!(inspect::parse_address("0x1000") == Ok(4096));
+    assert!(inspect::parse_address("0xFF") == Ok(255));
+}
+
+#[test]
+fn parse_invalid_address() {
+    assert!(["", "0x", "1000h", "-1", "0xzz"]
+        .iter()
+        .all(|address| inspect::parse_address(address).is_err()));
+}
+
+#[test]
+fn inspect_header() {
+    let p = util::volume_path("inspect_header");
+    volume_with_block(&p, b"header");
+
+    let superblock = storage::volume_info(&p).unwrap();
+    let output = inspect::header(&superblock, false);
+
+    // peta blok yang disimpan ketika unmount tercatat sebagai fitur
+    // ro-compat
+    assert!({
+        output.json["size"] == NEONDB_FILE_DEFAULT_SIZE
+            && output.json["allocator"] == "Rss"
+            && output.json["unrecovered_log"] == false
+            && output.json["ro_compat_features"] == 1
+    });
+
+    assert!({
+        output.text.contains(&format!(
+            "size:               {}\n",
+            NEONDB_FILE_DEFAULT_SIZE
+        )) && output.text.contains("unrecovered log:    no\n")
+            && output.text.contains("ro-compat features: 0x1\n")
+    });
+}
+
+#[test]
+fn inspect_all_blocks() {
+    let p = util::volume_path("inspect_blocks");
+    let address = volume_with_block(&p, b"blocks");
+
+    let mut s = mount_read_only(&p);
+    let output = inspect::blocks(&mut s).unwrap();
+    let blocks = output.json.as_array().unwrap();
+
+    // head dan peta blok juga harus ditampilkan
+    assert!({
+        blocks[0]["address"] == NEONDB_FILE_ALLOCATABLE_START
+            && blocks[0]["state"] == "reserved"
+            && blocks
+                .iter()
+                .any(|b| b["state"] == "reserved" && b["address"] != NEONDB_FILE_ALLOCATABLE_START)
+    });
+
+    assert!({
+        blocks
+            .iter()
+            .any(|b| b["address"] == address && b["size"] == 6 && b["state"] == "used")
+            && blocks.iter().any(|b| b["state"] == "free")
+    });
+
+    assert!({
+        let addresses = blocks
+            .iter()
+            .map(|b| b["address"].as_u64().unwrap())
+            .collect::<Vec<u64>>();
+
+        addresses.windows(2).all(|pair| pair[0] < pair[1])
+    });
+}
+
+#[test]
+fn inspect_stats() {
+    let p = util::volume_path("inspect_stats");
+    volume_with_block(&p, b"stats");
+
+    let s = mount_read_only(&p);
+    let output = inspect::stats(&s).unwrap();
+
+    // blok milik pengguna, head, dan peta blok
+    assert!(output.json["used_blocks"] == 3 && output.json["free_blocks"] == 1);
+}
+
+#[test]
+fn dump_block() {
+    let p = util::volume_path("inspect_dump");
+    let address = volume_with_block(&p, b"hello, world!");
+
+    let mut s = mount_read_only(&p);
+
+    // alamat di tengah blok tetap menampilkan seluruh isi blok tersebut
+    let output = inspect::dump(&mut s, address + 4).unwrap();
+
+    assert!({
+        output.json["address"] == address
+            && output.json["size"] == 13
+            && output.json["payload"] == "68656c6c6f2c20776f726c6421"
+    });
+
+    assert!({
+        output
+            .text
+            .starts_with(&format!("{:012x}  68 65 6c", address))
+            && output.text.ends_with("|hello, world!|\n")
+    });
+
+    assert!(inspect::dump(&mut s, 0).is_err());
+}
@@ -0,0 +1,95 @@
+use super::util;
+use crate::volume;
+use storage::{AllocatorKind, Storage, NEONDB_FILE_DEFAULT_SIZE, NEONDB_FILE_MIN_SIZE};
+
+#[test]
+fn parse_size_with_suffix() {
+    assert!(volume::parse_size("4096") == Ok(4096));
+    assert!(volume::parse_size("64K") == Ok(64 << 10));
+    assert!(volume::parse_size("1m") == Ok(1 << 20));
+    assert!(volume::parse_size("2G") == Ok(2 << 30));
+}
+
+#[test]
+fn parse_invalid_size() {
+    assert!(["", "K", "12X", "-1", "1.5M", "99999999999G"]
+        .iter()
+        .all(|size| volume::parse_size(size).is_err()));
+}
+
+#[test]
+fn create_then_info() {
+    let p = util::volume_path("create_info");
+    let path = p.to_str().unwrap();
+
+    assert!({
+        let res = volume::create(&util::args(&[
+            path,
+            "--size",
+            "64K",
+            "--allocator",
+            "buddy",
+            "--json",
+        ]));
+
+        res == Ok(0)
+    });
+
+    assert!({
+        let superblock = storage::volume_info(&p).unwrap();
+
+        superblock.size == 64 << 10 && superblock.allocator == AllocatorKind::Buddy
+    });
+
+    assert!(volume::info(&util::args(&[path, "--json"])) == Ok(0));
+    assert!(volume::info(&util::args(&[path, "--verbose"])).is_err());
+}
+
+#[test]
+fn create_existing_volume() {
+    let p = util::volume_path("create_existing");
+    let path = p.to_str().unwrap();
+
+    volume::create(&util::args(&[path])).unwrap();
+    let old = storage::volume_info(&p).unwrap();
+
+    // volume yang sudah ada hanya ditimpa jika diberikan flag --force
+    assert!(volume::create(&util::args(&[path, "--size", "1M"])).is_err());
+
+    assert!({
+        volume::create(&util::args(&[path, "--size", "1M", "--force"])).unwrap();
+        let new = storage::volume_info(&p).unwrap();
+
+        new.size == 1 << 20 && new.uuid != old.uuid
+    });
+}
+
+#[test]
+fn create_force_keeps_old_volume_on_error() {
+    let p = util::volume_path("create_force_kept");
+    let path = p.to_str().unwrap();
+
+    volume::create(&util::args(&[path])).unwrap();
+
+    // ukuran volume baru tidak valid
+    assert!({
+        let size = (NEONDB_FILE_MIN_SIZE - 1).to_string();
+        let res = volume::create(&util::args(&[path, "--size", &size, "--force"]));
+
+        res.is_err() && storage::volume_info(&p).unwrap().size == NEONDB_FILE_DEFAULT_SIZE
+    });
+
+    // volume sedang dimounting oleh pihak lain
+    let mut s = Storage::new();
+    s.mount(&p).unwrap();
+
+    assert!({
+        let res = volume::create(&util::args(&[path, "--size", "1M", "--force"]));
+
+        res.is_err_and(|err| err.contains("locked"))
+    });
+
+    assert!(
+        s.unmount().is_ok() && storage::volume_info(&p).unwrap().size == NEONDB_FILE_DEFAULT_SIZE
+    );
+}
@@ -0,0 +1,19 @@
+use std::fs;
+use std::path::PathBuf;
+
+// Path untuk volume milik sebuah test, dimana volume (beserta log-nya)
+// yang tersisa dari test sebelumnya sudah dihapus. Tiap test menggunakan
+// nama yang berbeda, sehingga test tidak perlu dijalankan secara serial.
+pub fn volume_path(name: &str) -> PathBuf {
+    fs::create_dir_all("tmp/neondb").unwrap();
+
+    let path = PathBuf::from(format!("tmp/neondb/{}.neondb", name));
+    let _ = fs::remove_file(&path);
+    let _ = fs::remove_file(format!("tmp/neondb/{}.neondb-wal", name));
+
+    path
+}
+
+pub fn args(args: &[&str]) -> Vec<String> {
+    args.iter().map(|arg| arg.to_string()).collect()
+}
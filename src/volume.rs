@@ -0,0 +1,91 @@
+use super::inspect;
+use super::USAGE;
+use storage::{AllocatorKind, FileBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+
+use std::path::Path;
+
+// Membuat volume baru, lalu menampilkan superblock dari volume tersebut.
+// Volume yang sudah ada hanya akan ditimpa jika diberikan flag --force.
+pub fn create(args: &[String]) -> Result<i32, String> {
+    let mut path = None;
+    let mut size = NEONDB_FILE_DEFAULT_SIZE;
+    let mut allocator = AllocatorKind::Rss;
+    let mut is_force = false;
+    let mut is_json = false;
+
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--size" => size = parse_size(args.next().ok_or(USAGE)?)?,
+            "--allocator" => allocator = parse_allocator(args.next().ok_or(USAGE)?)?,
+            "--force" => is_force = true,
+            "--json" => is_json = true,
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(Path::new(arg)),
+            _ => return Err(USAGE.to_string()),
+        }
+    }
+
+    let path = path.ok_or(USAGE)?;
+
+    // volume lama (jika ada) tetap dikunci hingga volume baru selesai
+    // dibuat, dan tidak akan dihapus jika ukuran volume baru tidak valid
+    let mut s: Storage<FileBackend> = Storage::with_allocator(allocator);
+
+    let res = if is_force {
+        s.mount_new_overwrite(path, size)
+    } else {
+        s.mount_new(path, size)
+    };
+    res.map_err(|err| err.to_string())?;
+
+    s.unmount().map_err(|err| err.to_string())?;
+
+    // superblock baru dibaca setelah unmount, karena peta blok milik
+    // allocator baru dicatat ketika volume di-unmount
+    let superblock = storage::volume_info(path).map_err(|err| err.to_string())?;
+    inspect::header(&superblock, false).print(is_json);
+
+    Ok(0)
+}
+
+// Menampilkan superblock dari volume yang sudah ada, tanpa melakukan
+// mounting terhadap volume tersebut.
+pub fn info(args: &[String]) -> Result<i32, String> {
+    let (path, is_json) = match args {
+        [path] => (path, false),
+        [path, flag] if flag == "--json" => (path, true),
+        _ => return Err(USAGE.to_string()),
+    };
+
+    let path = Path::new(path);
+
+    let superblock = storage::volume_info(path).map_err(|err| err.to_string())?;
+    inspect::header(&superblock, storage::has_unrecovered_log(path)).print(is_json);
+
+    Ok(0)
+}
+
+// Ukuran dapat diberi akhiran K, M, maupun G (kelipatan 1024)
+pub fn parse_size(size: &str) -> Result<u64, String> {
+    let (digits, shift) = match size.char_indices().last() {
+        Some((i, 'K')) | Some((i, 'k')) => (&size[..i], 10),
+        Some((i, 'M')) | Some((i, 'm')) => (&size[..i], 20),
+        Some((i, 'G')) | Some((i, 'g')) => (&size[..i], 30),
+        _ => (size, 0),
+    };
+
+    digits
+        .parse::<u64>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or(format!("invalid size: {}", size))
+}
+
+fn parse_allocator(allocator: &str) -> Result<AllocatorKind, String> {
+    match allocator {
+        "rss" => Ok(AllocatorKind::Rss),
+        "buddy" => Ok(AllocatorKind::Buddy),
+        _ => Err(format!("unknown allocator: {}", allocator)),
+    }
+}
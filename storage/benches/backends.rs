@@ -0,0 +1,82 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use storage::{
+    FileBackend, MmapBackend, PathBackend, Storage, SyncPolicy, NEONDB_FILE_DEFAULT_SIZE,
+};
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+fn volume_path(name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("neondb-bench-{}.neondb", name));
+
+    if path.exists() {
+        fs::remove_file(&path).unwrap();
+    }
+    path
+}
+
+fn fresh_storage<B: PathBackend>(name: &str) -> Storage<B> {
+    // fsync akan mendominasi hasil pengukuran, sehingga perbedaan antar
+    // backend tidak lagi terlihat
+    let mut s = Storage::default();
+    s.set_sync_policy(SyncPolicy::Never);
+    s.mount_new(&volume_path(name), NEONDB_FILE_DEFAULT_SIZE)
+        .unwrap();
+
+    s
+}
+
+fn bench_read_write<B: PathBackend>(c: &mut Criterion, name: &str) {
+    let mut s: Storage<B> = fresh_storage(name);
+    let address = s.alloc(4096).unwrap();
+
+    let mut group = c.benchmark_group("read_write");
+
+    for size in [64usize, 4096].iter() {
+        let bytes = vec![1u8; *size];
+        let mut buff = vec![0u8; *size];
+
+        group.bench_with_input(
+            BenchmarkId::new(format!("{}/write", name), size),
+            size,
+            |b, _| b.iter(|| s.write(address, &bytes).unwrap()),
+        );
+
+        group.bench_with_input(
+            BenchmarkId::new(format!("{}/read", name), size),
+            size,
+            |b, _| b.iter(|| s.read(address, &mut buff).unwrap()),
+        );
+    }
+
+    group.finish();
+}
+
+// Setiap alokasi dan dealokasi memperbarui metadata blok di volume
+fn bench_alloc_dealloc<B: PathBackend>(c: &mut Criterion, name: &str) {
+    let mut s: Storage<B> = fresh_storage(name);
+
+    // beberapa blok agar metadata blok sebelumnya juga ikut diperbarui
+    for _ in 0..16 {
+        s.alloc(64).unwrap();
+    }
+
+    c.bench_function(&format!("alloc_dealloc/{}", name), |b| {
+        b.iter(|| {
+            let address = s.alloc(64).unwrap();
+            s.dealloc(address).unwrap();
+        })
+    });
+}
+
+fn backends(c: &mut Criterion) {
+    bench_read_write::<FileBackend>(c, "file");
+    bench_read_write::<MmapBackend>(c, "mmap");
+
+    bench_alloc_dealloc::<FileBackend>(c, "file");
+    bench_alloc_dealloc::<MmapBackend>(c, "mmap");
+}
+
+criterion_group!(benches, backends);
+criterion_main!(benches);
@@ -11,12 +11,13 @@ fn main() {
         fs::create_dir_all(storage_path).unwrap();
     }
 
-    let files = vec!["invalid.txt", "invalid.neondb"];
+    let files = ["invalid.txt", "invalid.neondb"];
     for file in files.iter() {
         let fp = storage_path.join(file);
         if !fp.exists() {
             OpenOptions::new()
                 .create(true)
+                .truncate(false)
                 .write(true)
                 .open(storage_path.join(file))
                 .unwrap();
@@ -1,18 +1,207 @@
-use super::{ErrorKind, Result, NEONDB_FILE_ALLOCATABLE_SIZE, NEONDB_FILE_ALLOCATABLE_START};
+use buddyalloc::BuddyAllocator;
+use rssalloc::RSSAllocator;
 
-use std::fs::File;
+use std::collections::BTreeMap;
 
-pub trait Allocator {
-    fn alloc(&mut self, vol: &mut File, size: usize) -> Result<u64>;
-    fn dealloc(&mut self, vol: &mut File, address: u64) -> Result<()>;
+use super::{
+    AllocationStrategy, ChecksumMode, Error, ErrorKind, Result, VolumeBackend,
+    NEONDB_FILE_ALLOCATABLE_START,
+};
+
+pub trait Allocator<B: VolumeBackend> {
+    fn kind(&self) -> AllocatorKind;
+
+    fn alloc(&mut self, vol: &mut B, size: usize) -> Result<u64>;
+    fn dealloc(&mut self, vol: &mut B, address: u64) -> Result<()>;
+
+    // Sama seperti `alloc`, namun alamat yang dihasilkan merupakan
+    // kelipatan dari align (yang selalu berupa pangkat dua).
+    fn alloc_aligned(&mut self, _vol: &mut B, _size: usize, align: u64) -> Result<u64> {
+        Err(Error::new(ErrorKind::InvalidAlignment).with_actual(align))
+    }
 
     // Kedua method di atas tidak dapat dijalankan jika allocator
     // belum diinisialisasikan terlebih dulu.
-    fn init(&mut self, vol: &mut File) -> Result<Vec<Block>>;
-    fn init_new(&mut self, vol: &mut File) -> Result<()>;
+    //
+    // Parameter size merupakan ukuran total dari volume (termasuk
+    // superblock), sebagaimana yang tercatat di superblock.
+    fn init(&mut self, vol: &mut B, size: u64) -> Result<Vec<Block>>;
+    fn init_new(&mut self, vol: &mut B, size: u64) -> Result<()>;
+
+    // Menambahkan ruang kosong di akhir volume, setelah file volume
+    // diperbesar menjadi new_size byte.
+    fn extend(&mut self, vol: &mut B, new_size: u64) -> Result<()>;
+
+    fn blocks(&self, vol: &mut B) -> Vec<Block>;
+
+    // Ruang-ruang kosong yang terurut berdasarkan alamatnya. Berbeda
+    // dengan blocks, alamat dan ukuran di sini merupakan nilai sebenarnya
+    // (tanpa abstraksi), karena ruang kosong tidak memiliki metadata.
+    fn free_blocks(&self) -> Vec<Block>;
+
+    // Blok-blok milik allocator sendiri (misalnya head maupun peta blok)
+    // yang tidak ditampilkan oleh blocks, terurut berdasarkan alamatnya.
+    // Sama seperti free_blocks, alamat dan ukurannya merupakan nilai
+    // sebenarnya.
+    fn reserved_blocks(&self) -> Vec<Block>;
+    fn stats(&self) -> AllocationStats;
+
+    // Mengubah ukuran blok pada alamat yang diberikan tanpa memindahkannya,
+    // dan mengembalikan false jika hal tersebut tidak memungkinkan (isi dari
+    // blok tidak berubah).
+    fn resize(&mut self, vol: &mut B, address: u64, _new_size: usize) -> Result<bool> {
+        let is_block = self.blocks(vol).iter().any(|b| b.address == address);
+
+        if !is_block {
+            return Err(Error::new(ErrorKind::BlockNotFound).with_address(address));
+        }
+        Ok(false)
+    }
+
+    // Memindahkan blok-blok yang sedang digunakan (beserta isinya) agar
+    // tidak ada lagi celah di antara blok-blok tersebut, dan mengembalikan
+    // alamat lama -> alamat baru dari blok-blok yang dipindahkan.
+    //
+    // Allocator yang tidak mendukung compaction cukup mengembalikan peta
+    // kosong.
+    fn compact(&mut self, _vol: &mut B) -> Result<BTreeMap<u64, u64>> {
+        Ok(BTreeMap::new())
+    }
+
+    // Strategi tidak ikut terhapus oleh `reset`. Allocator yang hanya
+    // memiliki satu strategi cukup mengabaikannya.
+    fn set_strategy(&mut self, _strategy: AllocationStrategy) {}
+
+    // Cakupan checksum dari metadata tiap blok, yang harus diatur sebelum
+    // allocator diinisialisasi dan tidak ikut terhapus oleh `reset`.
+    // Allocator yang tidak mendukung checksum cukup menggunakan
+    // implementasi default.
+    fn set_checksum_mode(&mut self, mode: ChecksumMode) -> Result<()> {
+        match mode {
+            ChecksumMode::Disabled => Ok(()),
+            _ => Err(ErrorKind::VolumeUnsupportedFeatures.into()),
+        }
+    }
+
+    // Menghitung ulang checksum dari blok tempat alamat yang diberikan
+    // berada, setelah isi blok tersebut diubah secara langsung.
+    fn update_checksum(&mut self, _vol: &mut B, _address: u64) -> Result<()> {
+        Ok(())
+    }
+
+    // Memeriksa checksum dari blok tempat alamat yang diberikan berada,
+    // dan menghasilkan error `ErrorKind::ChecksumMismatch` jika tidak
+    // sesuai.
+    fn verify_checksum(&self, _vol: &mut B, _address: u64) -> Result<()> {
+        Ok(())
+    }
 
-    fn blocks(&self, vol: &mut File) -> Vec<Block>;
     fn reset(&mut self);
+
+    // Menyimpan peta dari seluruh blok ke dalam sebuah blok baru di
+    // volume, sehingga mounting berikutnya tidak perlu memindai seluruh
+    // blok. Blok tersebut tidak ikut ditampilkan oleh `blocks`, dan
+    // dapat dibebaskan kembali dengan `dealloc`.
+    //
+    // Generasi yang diberikan ikut dicatat di dalam peta, sehingga peta
+    // yang tidak berasal dari penyimpanan yang tercatat di superblock
+    // dapat dikenali.
+    //
+    // Allocator yang tidak mendukung peta blok cukup mengembalikan None.
+    fn save_map(&mut self, _vol: &mut B, _generation: u32) -> Result<Option<BlockMapRef>> {
+        Ok(None)
+    }
+
+    // Alternatif dari `init`, yaitu dengan memuat peta blok yang
+    // sebelumnya disimpan oleh `save_map`. Error jika peta tersebut
+    // tidak valid, termasuk jika generasinya berbeda.
+    fn load_map(
+        &mut self,
+        _vol: &mut B,
+        _size: u64,
+        _map: &BlockMapRef,
+        _generation: u32,
+    ) -> Result<()> {
+        Err(ErrorKind::VolumeCorrupted.into())
+    }
+}
+
+/// Statistik penggunaan ruang di dalam volume, yang dapat digunakan untuk
+/// membandingkan fragmentasi dari tiap `AllocationStrategy`.
+///
+/// Seluruh ukuran dalam byte, dan sudah termasuk metadata milik allocator.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct AllocationStats {
+    /// Jumlah blok yang sedang digunakan (termasuk blok milik allocator).
+    pub used_blocks: u64,
+    pub used_bytes: u64,
+
+    /// Jumlah blok kosong, dimana blok-blok kosong yang bersebelahan
+    /// sudah digabungkan menjadi satu (jika memungkinkan).
+    pub free_blocks: u64,
+    pub free_bytes: u64,
+
+    pub largest_free_block: u64,
+}
+
+impl AllocationStats {
+    /// Rasio dari ruang kosong yang tidak dapat digunakan untuk satu
+    /// alokasi yang besar, yaitu antara 0 (seluruh ruang kosong berada
+    /// di satu blok) hingga mendekati 1.
+    pub fn fragmentation(&self) -> f64 {
+        if self.free_bytes == 0 {
+            return 0.0;
+        }
+
+        1.0 - self.largest_free_block as f64 / self.free_bytes as f64
+    }
+}
+
+/// Lokasi dari peta blok yang disimpan oleh allocator, sebagaimana yang
+/// tercatat di superblock.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct BlockMapRef {
+    pub address: u64,
+    pub entries: u64,
+    pub checksum: u32,
+}
+
+/// Jenis-jenis allocator, yang identitasnya dicatat di superblock agar
+/// volume selalu dimounting dengan allocator yang sama.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AllocatorKind {
+    /// Really-Simple Storage Allocator, yaitu allocator berbasis
+    /// linked-list dengan strategi best-fit.
+    #[default]
+    Rss,
+
+    /// Buddy allocator, dimana ukuran tiap blok selalu dibulatkan ke
+    /// pangkat dua terdekat (termasuk metadata dari blok tersebut).
+    Buddy,
+}
+
+impl AllocatorKind {
+    pub(crate) fn id(&self) -> u32 {
+        match self {
+            AllocatorKind::Rss => 1,
+            AllocatorKind::Buddy => 2,
+        }
+    }
+
+    pub(crate) fn from_id(id: u32) -> Option<AllocatorKind> {
+        match id {
+            1 => Some(AllocatorKind::Rss),
+            2 => Some(AllocatorKind::Buddy),
+            _ => None,
+        }
+    }
+}
+
+pub fn new_allocator<B: VolumeBackend>(kind: AllocatorKind) -> Box<dyn Allocator<B>> {
+    match kind {
+        AllocatorKind::Rss => Box::new(RSSAllocator::new()),
+        AllocatorKind::Buddy => Box::new(BuddyAllocator::new()),
+    }
 }
 
 #[derive(Debug, Eq, PartialEq)]
@@ -21,4 +210,6 @@ pub struct Block {
     pub size: u64,
 }
 
+pub mod buddyalloc;
 pub mod rssalloc;
+pub mod slaballoc;
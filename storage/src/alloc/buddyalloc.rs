@@ -0,0 +1,372 @@
+use super::*;
+use crate::ops::Ops;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+
+const NULL_ADDRESS: u64 = 0;
+
+// Ukuran blok terkecil adalah 2^MIN_ORDER byte (termasuk metadata),
+// sedangkan ukuran blok terbesar dibatasi oleh tipe u64.
+const MIN_ORDER: u32 = 5;
+const MAX_ORDER: u32 = 63;
+
+mod init;
+
+/// Buddy Allocator.
+///
+/// Seluruh ruang yang dapat dialokasikan dipecah menjadi blok-blok
+/// berukuran pangkat dua. Ketika dialokasikan, sebuah blok kosong akan
+/// dibelah dua secara berulang hingga ukurannya paling sesuai, dan
+/// ketika didealokasi, blok tersebut akan digabungkan kembali dengan
+/// pasangannya (buddy) selama pasangan tersebut juga kosong.
+///
+/// Sama seperti `RSSAllocator`, blok-blok yang sedang digunakan saling
+/// terhubung melalui metadata di bagian awal tiap blok (diawali oleh
+/// head), sehingga blok-blok kosong dapat disusun ulang pada saat
+/// mounting.
+pub struct BuddyAllocator {
+    // blok-blok yang sedang digunakan (termasuk head), alamat -> order
+    used_blocks: BTreeMap<u64, u32>,
+
+    // daftar alamat blok kosong untuk tiap order
+    free_blocks: Vec<BTreeSet<u64>>,
+
+    volume_size: u64,
+    is_initialized: bool,
+}
+
+impl BuddyAllocator {
+    // Metadata di bagian awal tiap blok yang sedang digunakan:
+    //
+    // 8 byte untuk ukuran blok, dan
+    // 8 byte sisanya untuk alamat blok selanjutnya
+    const META_SIZE: u64 = 16;
+
+    pub fn new() -> BuddyAllocator {
+        BuddyAllocator {
+            used_blocks: BTreeMap::new(),
+            free_blocks: vec![BTreeSet::new(); MAX_ORDER as usize + 1],
+            volume_size: 0,
+            is_initialized: false,
+        }
+    }
+
+    fn mark_block<B: VolumeBackend>(&self, address: u64, vol: &mut B) -> Result<()> {
+        let size = 1u64 << self.used_blocks[&address];
+
+        let next_block_address = self
+            .used_blocks
+            .range(address + 1..)
+            .next()
+            .map(|(next, _)| *next)
+            .unwrap_or(NULL_ADDRESS);
+
+        let mut meta = Vec::with_capacity(BuddyAllocator::META_SIZE as usize);
+        meta.extend_from_slice(&size.to_be_bytes());
+        meta.extend_from_slice(&next_block_address.to_be_bytes());
+
+        Ops::write(address, &meta, vol)?;
+
+        Ok(())
+    }
+
+    fn mark_block_before<B: VolumeBackend>(&self, address: u64, vol: &mut B) -> Result<()> {
+        let prev_block_address = self.used_blocks.range(..address).next_back();
+
+        match prev_block_address {
+            Some((prev, _)) => self.mark_block(*prev, vol),
+            None => Ok(()),
+        }
+    }
+
+    // Order terkecil yang dapat menampung size byte (termasuk metadata)
+    fn order_of(size: u64) -> Option<u32> {
+        let real_size = size.checked_add(BuddyAllocator::META_SIZE)?;
+        let order = real_size.checked_next_power_of_two()?.trailing_zeros();
+
+        Some(order.max(MIN_ORDER))
+    }
+
+    // Alamat-alamat di bawah ini relatif terhadap awal ruang yang dapat
+    // dialokasikan, sehingga pasangan dari sebuah blok cukup didapatkan
+    // dengan membalik bit ke-order dari alamatnya.
+    fn relative(address: u64) -> u64 {
+        address - NEONDB_FILE_ALLOCATABLE_START
+    }
+
+    fn absolute(offset: u64) -> u64 {
+        offset + NEONDB_FILE_ALLOCATABLE_START
+    }
+
+    fn take_free_block(&mut self, order: u32) -> Option<u64> {
+        let found = (order..=MAX_ORDER).find(|o| !self.free_blocks[*o as usize].is_empty())?;
+
+        let offset = *self.free_blocks[found as usize].iter().next().unwrap();
+        self.free_blocks[found as usize].remove(&offset);
+
+        // belah dua hingga didapatkan blok dengan order yang diminta,
+        // dimana belahan bagian akhir selalu menjadi blok kosong
+        for o in (order..found).rev() {
+            self.free_blocks[o as usize].insert(offset + (1 << o));
+        }
+
+        Some(offset)
+    }
+
+    // Menjadikan blok yang diberikan sebagai blok kosong, sekaligus
+    // menggabungkannya dengan pasangannya selama pasangan tersebut juga
+    // kosong.
+    fn free_block(&mut self, mut offset: u64, mut order: u32) {
+        while order < MAX_ORDER {
+            let buddy = offset ^ (1 << order);
+
+            if !self.free_blocks[order as usize].remove(&buddy) {
+                break;
+            }
+
+            offset = offset.min(buddy);
+            order += 1;
+        }
+
+        self.free_blocks[order as usize].insert(offset);
+    }
+
+    // Memecah ruang [start, end) menjadi blok-blok terbesar yang
+    // alamatnya sejajar (aligned) dengan ukurannya masing-masing
+    fn aligned_chunks(start: u64, end: u64) -> Vec<(u64, u32)> {
+        let mut chunks = vec![];
+        let mut offset = start;
+
+        while offset < end {
+            let align = if offset == 0 {
+                MAX_ORDER
+            } else {
+                offset.trailing_zeros().min(MAX_ORDER)
+            };
+            let fit = 63 - (end - offset).leading_zeros();
+            let order = align.min(fit);
+
+            chunks.push((offset, order));
+            offset += 1 << order;
+        }
+
+        chunks
+    }
+
+    fn largest_free_block_size(&self) -> u64 {
+        (MIN_ORDER..=MAX_ORDER)
+            .rev()
+            .find(|o| !self.free_blocks[*o as usize].is_empty())
+            .map(|o| 1 << o)
+            .unwrap_or(0)
+    }
+}
+
+impl<B: VolumeBackend> Allocator<B> for BuddyAllocator {
+    fn kind(&self) -> AllocatorKind {
+        AllocatorKind::Buddy
+    }
+
+    fn alloc(&mut self, vol: &mut B, size: usize) -> Result<u64> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+
+        let size: u64 = size.try_into().unwrap();
+
+        let found = BuddyAllocator::order_of(size)
+            .and_then(|order| self.take_free_block(order).map(|offset| (offset, order)));
+
+        let (offset, order) = found.ok_or_else(|| {
+            let real_size = BuddyAllocator::order_of(size)
+                .map(|order| 1 << order)
+                .unwrap_or(u64::MAX);
+
+            Error::new(ErrorKind::VolumeNotEnoughSpace)
+                .with_sizes(real_size, self.largest_free_block_size())
+        })?;
+
+        let address = BuddyAllocator::absolute(offset);
+        self.used_blocks.insert(address, order);
+
+        self.mark_block_before(address, vol)?;
+        self.mark_block(address, vol)?;
+
+        let abstract_address = address + BuddyAllocator::META_SIZE;
+        Ok(abstract_address)
+    }
+
+    // Awal dari tiap blok selalu sejajar dengan ukuran blok terkecil,
+    // sehingga alamat (setelah metadata) hanya sejajar dengan ukuran
+    // metadata.
+    fn alloc_aligned(&mut self, vol: &mut B, size: usize, align: u64) -> Result<u64> {
+        if align > BuddyAllocator::META_SIZE {
+            return Err(Error::new(ErrorKind::InvalidAlignment)
+                .with_sizes(BuddyAllocator::META_SIZE, align));
+        }
+
+        self.alloc(vol, size)
+    }
+
+    fn dealloc(&mut self, vol: &mut B, address: u64) -> Result<()> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+
+        // head tidak dapat didealokasi
+        let real_address = address
+            .checked_sub(BuddyAllocator::META_SIZE)
+            .filter(|a| *a != NEONDB_FILE_ALLOCATABLE_START)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        let order = self
+            .used_blocks
+            .remove(&real_address)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        self.free_block(BuddyAllocator::relative(real_address), order);
+        self.mark_block_before(real_address, vol)?;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, vol: &mut B, address: u64, new_size: usize) -> Result<bool> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+
+        let real_address = address
+            .checked_sub(BuddyAllocator::META_SIZE)
+            .filter(|a| *a != NEONDB_FILE_ALLOCATABLE_START)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        let order = *self
+            .used_blocks
+            .get(&real_address)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        // Blok hanya dapat diperkecil, yaitu dengan membebaskan belahan
+        // bagian akhirnya secara berulang
+        let new_order = match BuddyAllocator::order_of(new_size.try_into().unwrap()) {
+            Some(new_order) if new_order <= order => new_order,
+            _ => return Ok(false),
+        };
+
+        if new_order < order {
+            let offset = BuddyAllocator::relative(real_address);
+
+            for o in (new_order..order).rev() {
+                self.free_block(offset + (1 << o), o);
+            }
+
+            self.used_blocks.insert(real_address, new_order);
+            self.mark_block(real_address, vol)?;
+        }
+
+        Ok(true)
+    }
+
+    fn init(&mut self, vol: &mut B, size: u64) -> Result<Vec<Block>> {
+        self.volume_size = size;
+
+        let start_address = init::obtain_head(vol, self)?;
+
+        init::scan_blocks(vol, start_address, self)?;
+        init::collect_free_blocks(self);
+
+        self.is_initialized = true;
+        Ok(self.blocks(vol))
+    }
+
+    fn init_new(&mut self, vol: &mut B, size: u64) -> Result<()> {
+        self.volume_size = size;
+
+        init::new_volume(vol, self)?;
+
+        self.is_initialized = true;
+        Ok(())
+    }
+
+    fn extend(&mut self, _vol: &mut B, new_size: u64) -> Result<()> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+        debug_assert!(new_size > self.volume_size);
+
+        let start = BuddyAllocator::relative(self.volume_size);
+        let end = BuddyAllocator::relative(new_size);
+
+        for (offset, order) in BuddyAllocator::aligned_chunks(start, end) {
+            self.free_block(offset, order);
+        }
+
+        self.volume_size = new_size;
+        Ok(())
+    }
+
+    fn blocks(&self, _vol: &mut B) -> Vec<Block> {
+        self.used_blocks
+            .iter()
+            .skip(1) // tidak perlu tampilkan head
+            .map(|(address, order)| Block {
+                // abstraksi
+                address: address + BuddyAllocator::META_SIZE,
+                size: (1 << order) - BuddyAllocator::META_SIZE,
+            })
+            .collect::<Vec<Block>>()
+    }
+
+    fn free_blocks(&self) -> Vec<Block> {
+        let mut blocks = self
+            .free_blocks
+            .iter()
+            .enumerate()
+            .flat_map(|(order, offsets)| {
+                offsets.iter().map(move |offset| Block {
+                    address: BuddyAllocator::absolute(*offset),
+                    size: 1 << order,
+                })
+            })
+            .collect::<Vec<Block>>();
+
+        blocks.sort_by_key(|block| block.address);
+        blocks
+    }
+
+    // Buddy allocator tidak memiliki peta blok, sehingga hanya head
+    fn reserved_blocks(&self) -> Vec<Block> {
+        self.used_blocks
+            .iter()
+            .take(1)
+            .map(|(address, order)| Block {
+                address: *address,
+                size: 1 << order,
+            })
+            .collect::<Vec<Block>>()
+    }
+
+    fn stats(&self) -> AllocationStats {
+        let free_bytes = self
+            .free_blocks
+            .iter()
+            .enumerate()
+            .map(|(order, offsets)| offsets.len() as u64 * (1 << order))
+            .sum();
+
+        AllocationStats {
+            used_blocks: self.used_blocks.len() as u64,
+            used_bytes: self.used_blocks.values().map(|order| 1 << order).sum(),
+            free_blocks: self.free_blocks.iter().map(|o| o.len() as u64).sum(),
+            free_bytes,
+            largest_free_block: self.largest_free_block_size(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.used_blocks.clear();
+        self.free_blocks.iter_mut().for_each(BTreeSet::clear);
+        self.volume_size = 0;
+        self.is_initialized = false;
+    }
+}
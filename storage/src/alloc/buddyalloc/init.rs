@@ -0,0 +1,121 @@
+use super::*;
+
+pub fn obtain_head<B: VolumeBackend>(vol: &mut B, allocator: &mut BuddyAllocator) -> Result<u64> {
+    let address = NEONDB_FILE_ALLOCATABLE_START;
+    let mut buff = [0u8; 16];
+
+    Ops::read(address, &mut buff, vol)?;
+
+    let (size, next_address) = extract_values(&buff);
+
+    if size != 1 << MIN_ORDER {
+        return Err(Error::new(ErrorKind::VolumeCorrupted)
+            .with_address(address)
+            .with_index(0)
+            .with_sizes(1 << MIN_ORDER, size));
+    }
+
+    allocator.used_blocks.insert(address, MIN_ORDER);
+    Ok(next_address)
+}
+
+pub fn new_volume<B: VolumeBackend>(vol: &mut B, allocator: &mut BuddyAllocator) -> Result<()> {
+    debug_assert!(allocator.used_blocks.is_empty());
+
+    // Head menempati blok terkecil di bagian paling awal
+    allocator
+        .used_blocks
+        .insert(NEONDB_FILE_ALLOCATABLE_START, MIN_ORDER);
+    allocator.mark_block(NEONDB_FILE_ALLOCATABLE_START, vol)?;
+
+    collect_free_blocks(allocator);
+
+    Ok(())
+}
+
+pub fn scan_blocks<B: VolumeBackend>(
+    vol: &mut B,
+    start_address: u64,
+    allocator: &mut BuddyAllocator,
+) -> Result<()> {
+    let mut address = start_address;
+    let mut buff = [0u8; 16];
+
+    while address != NULL_ADDRESS {
+        let corrupted = || {
+            Error::new(ErrorKind::VolumeCorrupted)
+                .with_address(address)
+                .with_index(allocator.used_blocks.len())
+        };
+
+        // blok harus terletak setelah blok sebelumnya
+        let (last_address, last_order) = allocator.used_blocks.iter().next_back().unwrap();
+        if address < last_address + (1 << last_order) {
+            return Err(corrupted());
+        }
+
+        Ops::read(address, &mut buff, vol)?;
+
+        let (size, next_address) = extract_values(&buff);
+        let offset = BuddyAllocator::relative(address);
+
+        let is_valid = size.is_power_of_two()
+            && size >= 1 << MIN_ORDER
+            && offset.is_multiple_of(size)
+            && address
+                .checked_add(size)
+                .is_some_and(|end| end <= allocator.volume_size);
+
+        if !is_valid {
+            return Err(corrupted());
+        }
+
+        allocator.used_blocks.insert(address, size.trailing_zeros());
+
+        address = next_address;
+    }
+
+    Ok(())
+}
+
+// Menyusun blok-blok kosong dari ruang yang tidak ditempati oleh blok
+// yang sedang digunakan.
+pub fn collect_free_blocks(allocator: &mut BuddyAllocator) {
+    let end = BuddyAllocator::relative(allocator.volume_size);
+
+    for (offset, order) in BuddyAllocator::aligned_chunks(0, end) {
+        collect_free_blocks_in(allocator, offset, order);
+    }
+}
+
+fn collect_free_blocks_in(allocator: &mut BuddyAllocator, offset: u64, order: u32) {
+    let start = BuddyAllocator::absolute(offset);
+
+    if allocator.used_blocks.get(&start) == Some(&order) {
+        return;
+    }
+
+    let is_empty = allocator
+        .used_blocks
+        .range(start..start + (1 << order))
+        .next()
+        .is_none();
+
+    if is_empty {
+        allocator.free_blocks[order as usize].insert(offset);
+    } else {
+        // blok yang sedang digunakan selalu sejajar dengan ukurannya,
+        // sehingga cukup dicari di masing-masing belahan
+        collect_free_blocks_in(allocator, offset, order - 1);
+        collect_free_blocks_in(allocator, offset + (1 << (order - 1)), order - 1);
+    }
+}
+
+fn extract_values(bytes: &[u8]) -> (u64, u64) {
+    (bytes_to_u64(&bytes[..8]), bytes_to_u64(&bytes[8..]))
+}
+
+fn bytes_to_u64(bytes: &[u8]) -> u64 {
+    let bytes = bytes.try_into().unwrap();
+    u64::from_be_bytes(bytes)
+}
@@ -1,11 +1,25 @@
 use super::*;
 use crate::ops::Ops;
+use crate::ChecksumMode;
 use rssblock::RSSBlock;
 
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryInto;
 
 const NULL_ADDRESS: u64 = 0;
 
+// Peta blok diawali oleh header berisi generasi dari peta (4 byte) dan
+// 4 byte cadangan. Tiap entri berikutnya berisi alamat (8 byte) dan
+// ukuran (8 byte) dari sebuah blok yang sedang digunakan, termasuk head.
+const MAP_HEADER_SIZE: u64 = 8;
+const MAP_ENTRY_SIZE: u64 = 16;
+
+// Ukuran maksimal data yang dibaca dalam sekali baca, baik ketika blok
+// dipindahkan maupun ketika checksum dari isi blok dihitung
+const CHUNK_SIZE: u64 = 1 << 16;
+
+pub mod check;
 mod init;
 mod rssblock;
 
@@ -13,9 +27,35 @@ mod rssblock;
 ///
 /// Mengimplementasikan alokasi storage dengan menggunakan struktur
 /// data linked-list sebagai inti utamanya.
+///
+/// Di memori, blok-blok disimpan di dalam ordered map (berdasarkan alamat
+/// maupun ukuran), sehingga alloc, dealloc, maupun penggabungan blok
+/// kosong cukup membutuhkan waktu logaritmik.
 pub struct RSSAllocator {
-    blocks: Vec<RSSBlock>,
+    // blok-blok yang sedang digunakan (termasuk head), alamat -> ukuran
+    used_blocks: BTreeMap<u64, u64>,
+
+    // blok-blok kosong, masing-masing berdasarkan alamat (alamat ->
+    // ukuran) dan berdasarkan ukuran (untuk pencarian best-fit)
+    unused_blocks: BTreeMap<u64, u64>,
+    unused_by_size: BTreeSet<(u64, u64)>,
+
+    volume_size: u64,
     is_initialized: bool,
+
+    // alamat (abstrak) dari blok yang berisi peta blok, jika ada
+    map_address: Option<u64>,
+
+    strategy: AllocationStrategy,
+
+    // posisi awal pencarian untuk strategi NextFit
+    next_fit_address: u64,
+
+    checksums: ChecksumMode,
+
+    // checksum dari isi tiap blok terpakai (hanya untuk ChecksumMode::Full),
+    // alamat -> checksum
+    payload_checksums: BTreeMap<u64, u32>,
 }
 
 impl RSSAllocator {
@@ -23,170 +63,402 @@ impl RSSAllocator {
         // Blok-blok yang ada di dalam volume belum dapat dideteksi,
         // oleh karenanya perlu dilakukan inisialisasi terlebih dulu
         // sebelum alokator mulai digunakan.
-        let blocks = vec![];
-
         RSSAllocator {
-            blocks,
+            used_blocks: BTreeMap::new(),
+            unused_blocks: BTreeMap::new(),
+            unused_by_size: BTreeSet::new(),
+            volume_size: 0,
             is_initialized: false,
+            map_address: None,
+            strategy: AllocationStrategy::default(),
+            next_fit_address: 0,
+            checksums: ChecksumMode::default(),
+            payload_checksums: BTreeMap::new(),
         }
     }
 
-    fn mark_block(&mut self, index: usize, vol: &mut File) {
-        debug_assert!(self.blocks[index].is_used);
+    // Ukuran metadata dari tiap blok (termasuk head), yang bergantung pada
+    // cakupan checksum dari volume
+    fn meta_size(&self) -> u64 {
+        RSSBlock::meta_size_of(self.checksums)
+    }
+
+    // Ukuran blok (termasuk metadata) untuk menampung size byte, dimana
+    // ukuran yang tidak dapat direpresentasikan tidak akan pernah muat
+    fn real_size_of(&self, size: usize) -> Result<u64> {
+        (size as u64).checked_add(self.meta_size()).ok_or_else(|| {
+            Error::new(ErrorKind::VolumeNotEnoughSpace)
+                .with_sizes(u64::MAX, self.largest_unused_block_size())
+        })
+    }
+
+    fn mark_block<B: VolumeBackend>(&self, address: u64, vol: &mut B) -> Result<()> {
+        let block = RSSBlock {
+            address,
+            size: self.used_blocks[&address],
+        };
 
         let next_block_address = self
-            .find_used_block_address_after(index)
-            .or_else(|| Some(NULL_ADDRESS))
-            .unwrap();
+            .used_blocks
+            .range(address + 1..)
+            .next()
+            .map(|(next, _)| *next)
+            .unwrap_or(NULL_ADDRESS);
+
+        let checksum = match self.checksums {
+            ChecksumMode::Disabled => None,
+            ChecksumMode::Metadata => Some(0),
+            ChecksumMode::Full => Some(self.payload_checksums[&address]),
+        };
 
         Ops::write(
-            self.blocks[index].address,
-            &self.blocks[index].construct_meta(next_block_address),
+            block.address,
+            &block.construct_meta(next_block_address, checksum),
             vol,
-        );
+        )?;
+
+        Ok(())
     }
 
-    // Menandai block dengan posisi index sebelum index yang diberikan,
-    // dimana block tersebut bukanlah sebuah block kosong.
-    fn mark_block_before(&mut self, index: usize, vol: &mut File) {
-        let prev_block_index = &self.blocks[..index].iter().rposition(|b| b.is_used);
+    // Menandai blok terpakai yang terletak sebelum alamat yang diberikan
+    // (jika ada).
+    fn mark_block_before<B: VolumeBackend>(&self, address: u64, vol: &mut B) -> Result<()> {
+        let prev_block_address = self.used_blocks.range(..address).next_back();
 
-        if let Some(i) = prev_block_index {
-            self.mark_block(*i, vol);
+        match prev_block_address {
+            Some((prev, _)) => self.mark_block(*prev, vol),
+            None => Ok(()),
         }
     }
 
-    fn find_unused_block_index(&self, size: u64) -> Option<usize> {
-        self.blocks
-            .iter()
-            .enumerate()
-            .filter(|(_, b)| !b.is_used && b.size >= size)
-            .min_by_key(|(_, b)| b.size)
-            .and_then(|(i, _)| Some(i))
+    // Menghitung ulang checksum dari isi blok terpakai pada alamat yang
+    // diberikan, tanpa menulis ulang metadata dari blok tersebut.
+    fn refresh_checksum<B: VolumeBackend>(&mut self, address: u64, vol: &mut B) -> Result<()> {
+        if self.checksums == ChecksumMode::Full {
+            let meta_size = self.meta_size();
+            let size = self.used_blocks[&address] - meta_size;
+
+            let checksum = payload_checksum(vol, address + meta_size, size)?;
+            self.payload_checksums.insert(address, checksum);
+        }
+        Ok(())
     }
 
-    // Hanya mengambil bagian dari blok kosong, tetapi belum dilakukan
-    // reservasi blok baru (jika seandainya memang demikian)
-    fn get_unused_block(&mut self, index: usize, size: u64) -> u64 {
-        debug_assert!(!self.blocks[index].is_used);
-        debug_assert!(self.blocks[index].size >= size);
+    // Blok terpakai (alamat beserta ukurannya) yang menempati alamat yang
+    // diberikan
+    fn block_of(&self, address: u64) -> Option<(u64, u64)> {
+        self.used_blocks
+            .range(..=address)
+            .next_back()
+            .filter(|(block_address, size)| address < *block_address + *size)
+            .map(|(block_address, size)| (*block_address, *size))
+    }
 
-        let address = self.blocks[index].address;
+    fn insert_unused_block(&mut self, address: u64, size: u64) {
+        debug_assert!(size > 0);
 
-        self.blocks[index].size -= size;
+        self.unused_blocks.insert(address, size);
+        self.unused_by_size.insert((size, address));
+    }
 
-        if self.blocks[index].size == 0 {
-            self.blocks.remove(index);
-        } else {
-            self.blocks[index].address += size;
-        }
+    fn remove_unused_block(&mut self, address: u64) -> Option<u64> {
+        let size = self.unused_blocks.remove(&address)?;
+        self.unused_by_size.remove(&(size, address));
 
-        address
+        Some(size)
     }
 
-    fn merge_unused_blocks(&mut self, start_index: usize) {
-        debug_assert!(!self.blocks[start_index].is_used);
-        debug_assert!(start_index < self.blocks.len());
-
-        let mut new_size = self.blocks[start_index].size;
-        let i = start_index + 1;
+    // Mencari blok kosong yang dapat menampung size byte, sesuai dengan
+    // strategi yang digunakan
+    fn find_unused_block(&self, size: u64) -> Option<(u64, u64)> {
+        let fits = |(_, block_size): &(&u64, &u64)| **block_size >= size;
+
+        match self.strategy {
+            // jika terdapat beberapa, diambil yang alamatnya paling kecil
+            AllocationStrategy::BestFit => self
+                .unused_by_size
+                .range((size, 0)..)
+                .next()
+                .map(|(size, address)| (address, size)),
+
+            AllocationStrategy::FirstFit => self.unused_blocks.iter().find(fits),
+
+            AllocationStrategy::NextFit => self
+                .unused_blocks
+                .range(self.next_fit_address..)
+                .chain(self.unused_blocks.range(..self.next_fit_address))
+                .find(fits),
+
+            AllocationStrategy::WorstFit => self
+                .unused_by_size
+                .iter()
+                .next_back()
+                .filter(|(block_size, _)| *block_size >= size)
+                .map(|(size, address)| (address, size)),
+        }
+        .map(|(address, size)| (*address, *size))
+    }
 
-        loop {
-            if i == self.blocks.len() || self.blocks[i].is_used {
-                break;
-            }
+    // Hanya mengambil bagian awal dari blok kosong, tetapi belum dilakukan
+    // reservasi blok baru (jika seandainya memang demikian)
+    fn take_unused_block(&mut self, address: u64, size: u64) {
+        let block_size = self.remove_unused_block(address).unwrap();
+        debug_assert!(block_size >= size);
 
-            let block = self.blocks.remove(i);
-            new_size += block.size;
+        if block_size > size {
+            self.insert_unused_block(address + size, block_size - size);
         }
+    }
 
-        self.blocks[start_index].size = new_size;
+    // Menempatkan blok terpakai di [address, address + size) yang berada di
+    // dalam blok kosong pada alamat block_address. Ruang kosong sebelum
+    // maupun sesudahnya tetap menjadi blok kosong.
+    fn take_unused_space(&mut self, block_address: u64, address: u64, size: u64) {
+        let block_size = self.remove_unused_block(block_address).unwrap();
+        let end = block_address + block_size;
+
+        debug_assert!(block_address <= address && address + size <= end);
+
+        if block_address < address {
+            self.insert_unused_block(block_address, address - block_address);
+        }
+        if address + size < end {
+            self.insert_unused_block(address + size, end - address - size);
+        }
     }
 
-    fn find_used_block_index(&self, address: u64) -> Option<usize> {
-        self.blocks
-            .binary_search_by_key(&address, |b| b.address)
-            .and_then(|i| {
-                if self.blocks[i].is_used {
-                    Ok(i)
-                } else {
-                    Err(i)
-                }
+    // Mencari blok kosong pertama yang dapat menampung blok berukuran
+    // size, dimana alamat (abstrak) dari blok tersebut sejajar dengan
+    // align. Hasilnya adalah alamat dari blok kosong beserta alamat awal
+    // dari blok yang akan ditempatkan.
+    fn find_aligned_space(&self, size: u64, align: u64) -> Option<(u64, u64)> {
+        let meta_size = self.meta_size();
+
+        self.unused_blocks
+            .iter()
+            .find_map(|(block_address, block_size)| {
+                let address =
+                    (block_address + meta_size).checked_next_multiple_of(align)? - meta_size;
+
+                let end = address.checked_add(size)?;
+
+                (end <= block_address + block_size).then_some((*block_address, address))
             })
-            .ok()
     }
 
-    fn find_used_block_address_after(&self, index: usize) -> Option<u64> {
-        debug_assert!(index < self.blocks.len());
-
-        self.blocks
+    fn largest_unused_block_size(&self) -> u64 {
+        self.unused_by_size
             .iter()
-            .skip(index + 1)
-            .find(|b| b.is_used)
-            .and_then(|b| Some(b.address))
+            .next_back()
+            .map(|(size, _)| *size)
+            .unwrap_or(0)
     }
 
-    fn free_block(&mut self, index: usize) {
-        self.blocks[index].is_used = false;
+    // Menjadikan ruang yang diberikan sebagai blok kosong, sekaligus
+    // menggabungkannya dengan blok kosong yang bersebelahan.
+    fn free_space(&mut self, mut address: u64, mut size: u64) {
+        let prev = self
+            .unused_blocks
+            .range(..address)
+            .next_back()
+            .map(|(a, s)| (*a, *s));
+
+        if let Some((prev_address, prev_size)) = prev {
+            if prev_address + prev_size == address {
+                self.remove_unused_block(prev_address);
+
+                address = prev_address;
+                size += prev_size;
+            }
+        }
 
-        if index > 0 && !self.blocks[index - 1].is_used {
-            self.merge_unused_blocks(index - 1);
-        } else {
-            self.merge_unused_blocks(index);
+        if let Some(next_size) = self.remove_unused_block(address + size) {
+            size += next_size;
         }
+
+        self.insert_unused_block(address, size);
+    }
+}
+
+// Menyalin isi dari blok (tanpa metadata) ke alamat yang lebih kecil.
+// Penyalinan dilakukan secara berurutan mulai dari bagian awal blok,
+// sehingga blok tujuan boleh tumpang tindih dengan blok asal.
+fn move_block_data<B: VolumeBackend>(
+    vol: &mut B,
+    from: u64,
+    to: u64,
+    size: u64,
+    meta_size: u64,
+) -> Result<()> {
+    debug_assert!(to < from);
+
+    let mut buff = vec![0u8; cmp::min(size, CHUNK_SIZE) as usize];
+    let mut offset = meta_size;
+
+    while offset < size {
+        let len = cmp::min(size - offset, CHUNK_SIZE) as usize;
+
+        vol.read_exact_at(from + offset, &mut buff[..len])?;
+        vol.write_all_at(to + offset, &buff[..len])?;
+
+        offset += len as u64;
+    }
+
+    Ok(())
+}
+
+// Checksum (crc32c) dari size byte yang dimulai dari alamat yang diberikan
+fn payload_checksum<B: VolumeBackend>(vol: &mut B, address: u64, size: u64) -> Result<u32> {
+    let mut buff = vec![0u8; cmp::min(size, CHUNK_SIZE) as usize];
+    let mut checksum = 0;
+    let mut offset = 0;
+
+    while offset < size {
+        let len = cmp::min(size - offset, CHUNK_SIZE) as usize;
+
+        vol.read_exact_at(address + offset, &mut buff[..len])?;
+        checksum = crc32c::crc32c_append(checksum, &buff[..len]);
+
+        offset += len as u64;
     }
+
+    Ok(checksum)
+}
+
+// Generasi yang tercatat di header dari peta blok
+fn map_generation(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes(bytes[..4].try_into().unwrap())
 }
 
-impl Allocator for RSSAllocator {
-    fn alloc(&mut self, vol: &mut File, size: usize) -> Result<u64> {
+impl<B: VolumeBackend> Allocator<B> for RSSAllocator {
+    fn kind(&self) -> AllocatorKind {
+        AllocatorKind::Rss
+    }
+
+    fn alloc(&mut self, vol: &mut B, size: usize) -> Result<u64> {
         if !self.is_initialized {
-            return Err(ErrorKind::AllocatorNotInitialized);
+            return Err(ErrorKind::AllocatorNotInitialized.into());
         }
 
-        let size: u64 = size.try_into().unwrap();
-        let real_size = size + RSSBlock::META_SIZE;
+        let real_size = self.real_size_of(size)?;
 
-        let i = self
-            .find_unused_block_index(real_size)
-            .ok_or_else(|| ErrorKind::VolumeNotEnoughSpace)?;
+        let (address, _) = self.find_unused_block(real_size).ok_or_else(|| {
+            Error::new(ErrorKind::VolumeNotEnoughSpace)
+                .with_sizes(real_size, self.largest_unused_block_size())
+        })?;
 
-        let address = self.get_unused_block(i, real_size);
-        self.blocks.insert(
-            i,
-            RSSBlock {
-                address,
-                size: real_size,
-                is_used: true,
-            },
-        );
+        self.take_unused_block(address, real_size);
+        self.used_blocks.insert(address, real_size);
+        self.next_fit_address = address + real_size;
+        self.refresh_checksum(address, vol)?;
 
-        self.mark_block_before(i, vol);
-        self.mark_block(i, vol);
+        self.mark_block_before(address, vol)?;
+        self.mark_block(address, vol)?;
 
-        let abstract_address = address + RSSBlock::META_SIZE;
+        let abstract_address = address + self.meta_size();
         Ok(abstract_address)
     }
 
-    fn dealloc(&mut self, vol: &mut File, address: u64) -> Result<()> {
-        debug_assert!(address != NEONDB_FILE_ALLOCATABLE_START);
+    // Ruang kosong di antara blok sebelumnya dan blok yang sejajar tidak
+    // memerlukan metadata, sehingga tetap dapat digunakan oleh alokasi lain.
+    fn alloc_aligned(&mut self, vol: &mut B, size: usize, align: u64) -> Result<u64> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+        debug_assert!(align.is_power_of_two());
+
+        let real_size = self.real_size_of(size)?;
+
+        let (block_address, address) =
+            self.find_aligned_space(real_size, align).ok_or_else(|| {
+                Error::new(ErrorKind::VolumeNotEnoughSpace)
+                    .with_sizes(real_size, self.largest_unused_block_size())
+            })?;
+
+        self.take_unused_space(block_address, address, real_size);
+        self.used_blocks.insert(address, real_size);
+        self.next_fit_address = address + real_size;
+        self.refresh_checksum(address, vol)?;
+
+        self.mark_block_before(address, vol)?;
+        self.mark_block(address, vol)?;
 
+        Ok(address + self.meta_size())
+    }
+
+    fn dealloc(&mut self, vol: &mut B, address: u64) -> Result<()> {
         if !self.is_initialized {
-            return Err(ErrorKind::AllocatorNotInitialized);
+            return Err(ErrorKind::AllocatorNotInitialized.into());
         }
 
-        let real_address = address - RSSBlock::META_SIZE;
+        // head tidak dapat didealokasi
+        let real_address = address
+            .checked_sub(self.meta_size())
+            .filter(|a| *a != NEONDB_FILE_ALLOCATABLE_START)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        let size = self
+            .used_blocks
+            .remove(&real_address)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
 
-        let i = self
-            .find_used_block_index(real_address)
-            .ok_or_else(|| ErrorKind::BlockNotFound)?;
+        self.payload_checksums.remove(&real_address);
 
-        self.free_block(i);
-        self.mark_block_before(i, vol);
+        self.free_space(real_address, size);
+        self.mark_block_before(real_address, vol)?;
 
+        if self.map_address == Some(address) {
+            self.map_address = None;
+        }
         Ok(())
     }
 
-    fn init(&mut self, vol: &mut File) -> Result<Vec<Block>> {
+    fn resize(&mut self, vol: &mut B, address: u64, new_size: usize) -> Result<bool> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+
+        let real_address = address
+            .checked_sub(self.meta_size())
+            .filter(|a| *a != NEONDB_FILE_ALLOCATABLE_START)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        let size = *self
+            .used_blocks
+            .get(&real_address)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        let real_size = self.real_size_of(new_size)?;
+
+        if real_size <= size {
+            // sisa dari blok dikembalikan sebagai ruang kosong
+            if real_size < size {
+                self.free_space(real_address + real_size, size - real_size);
+            }
+        } else {
+            // blok kosong tepat setelah blok ini harus cukup untuk
+            // menampung kekurangannya
+            let next_address = real_address + size;
+            let additional = real_size - size;
+
+            match self.unused_blocks.get(&next_address) {
+                Some(next_size) if *next_size >= additional => {
+                    self.take_unused_block(next_address, additional)
+                }
+                _ => return Ok(false),
+            }
+        }
+
+        self.used_blocks.insert(real_address, real_size);
+        self.refresh_checksum(real_address, vol)?;
+        self.mark_block(real_address, vol)?;
+
+        Ok(true)
+    }
+
+    fn init(&mut self, vol: &mut B, size: u64) -> Result<Vec<Block>> {
+        self.volume_size = size;
+
         // disini sudah didapatkan posisi blok selanjutnya
         let start_address = init::obtain_head(vol, self)?;
 
@@ -196,28 +468,296 @@ impl Allocator for RSSAllocator {
         Ok(self.blocks(vol))
     }
 
-    fn init_new(&mut self, vol: &mut File) -> Result<()> {
+    fn init_new(&mut self, vol: &mut B, size: u64) -> Result<()> {
+        self.volume_size = size;
+
         init::new_volume(vol, self)?;
 
         self.is_initialized = true;
         Ok(())
     }
 
-    fn blocks(&self, _vol: &mut File) -> Vec<Block> {
-        self.blocks
+    fn extend(&mut self, _vol: &mut B, new_size: u64) -> Result<()> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+        debug_assert!(new_size > self.volume_size);
+
+        // Ruang tambahan cukup digabungkan dengan blok kosong terakhir
+        // (jika ada), karena blok kosong tidak memiliki metadata di volume.
+        let old_size = self.volume_size;
+
+        self.free_space(old_size, new_size - old_size);
+        self.volume_size = new_size;
+        Ok(())
+    }
+
+    fn blocks(&self, _vol: &mut B) -> Vec<Block> {
+        let meta_size = self.meta_size();
+
+        self.used_blocks
             .iter()
             .skip(1) // tidak perlu tampilkan head
-            .filter(|b| b.is_used)
-            .map(|b| Block {
+            .filter(|(address, _)| Some(*address + meta_size) != self.map_address)
+            .map(|(address, size)| Block {
                 // abstraksi
-                address: b.address + RSSBlock::META_SIZE,
-                size: b.size - RSSBlock::META_SIZE,
+                address: address + meta_size,
+                size: size - meta_size,
+            })
+            .collect::<Vec<Block>>()
+    }
+
+    fn free_blocks(&self) -> Vec<Block> {
+        self.unused_blocks
+            .iter()
+            .map(|(address, size)| Block {
+                address: *address,
+                size: *size,
+            })
+            .collect::<Vec<Block>>()
+    }
+
+    fn reserved_blocks(&self) -> Vec<Block> {
+        let meta_size = self.meta_size();
+
+        self.used_blocks
+            .iter()
+            .enumerate()
+            .filter(|(i, (address, _))| *i == 0 || Some(*address + meta_size) == self.map_address)
+            .map(|(_, (address, size))| Block {
+                address: *address,
+                size: *size,
             })
             .collect::<Vec<Block>>()
     }
 
+    fn stats(&self) -> AllocationStats {
+        AllocationStats {
+            used_blocks: self.used_blocks.len() as u64,
+            used_bytes: self.used_blocks.values().sum(),
+            free_blocks: self.unused_blocks.len() as u64,
+            free_bytes: self.unused_blocks.values().sum(),
+            largest_free_block: self.largest_unused_block_size(),
+        }
+    }
+
+    fn compact(&mut self, vol: &mut B) -> Result<BTreeMap<u64, u64>> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+        debug_assert!(self.map_address.is_none());
+
+        let meta_size = self.meta_size();
+
+        let mut remap = BTreeMap::new();
+        let mut compacted = BTreeMap::new();
+        let mut checksums = BTreeMap::new();
+
+        // head tidak pernah dipindahkan, sehingga blok-blok setelahnya
+        // cukup disusun ulang tepat setelah blok sebelumnya
+        let mut next_address = NEONDB_FILE_ALLOCATABLE_START;
+
+        for (address, size) in self.used_blocks.iter() {
+            if *address != next_address {
+                move_block_data(vol, *address, next_address, *size, meta_size)?;
+
+                remap.insert(address + meta_size, next_address + meta_size);
+            }
+
+            // isi blok tidak berubah, sehingga checksum-nya tetap sama
+            if let Some(checksum) = self.payload_checksums.get(address) {
+                checksums.insert(next_address, *checksum);
+            }
+
+            compacted.insert(next_address, *size);
+            next_address += size;
+        }
+
+        if remap.is_empty() {
+            return Ok(remap);
+        }
+
+        self.used_blocks = compacted;
+        self.payload_checksums = checksums;
+        self.unused_blocks.clear();
+        self.unused_by_size.clear();
+
+        if next_address < self.volume_size {
+            self.insert_unused_block(next_address, self.volume_size - next_address);
+        }
+        self.next_fit_address = next_address;
+
+        // metadata dari seluruh blok yang dipindahkan (beserta blok
+        // sebelumnya) harus ditulis ulang
+        let first_moved = remap.keys().next().unwrap() - meta_size;
+
+        self.mark_block_before(first_moved, vol)?;
+        for address in self.used_blocks.range(first_moved..).map(|(a, _)| *a) {
+            self.mark_block(address, vol)?;
+        }
+
+        Ok(remap)
+    }
+
+    fn set_strategy(&mut self, strategy: AllocationStrategy) {
+        self.strategy = strategy;
+    }
+
+    fn set_checksum_mode(&mut self, mode: ChecksumMode) -> Result<()> {
+        self.checksums = mode;
+        Ok(())
+    }
+
+    fn update_checksum(&mut self, vol: &mut B, address: u64) -> Result<()> {
+        if self.checksums != ChecksumMode::Full {
+            return Ok(());
+        }
+
+        let (real_address, _) = self
+            .block_of(address)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        self.refresh_checksum(real_address, vol)?;
+        self.mark_block(real_address, vol)
+    }
+
+    // Metadata dari blok selalu diperiksa, sedangkan isi blok hanya
+    // diperiksa untuk ChecksumMode::Full
+    fn verify_checksum(&self, vol: &mut B, address: u64) -> Result<()> {
+        if self.checksums == ChecksumMode::Disabled {
+            return Ok(());
+        }
+
+        let (real_address, size) = self
+            .block_of(address)
+            .ok_or_else(|| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        let meta_size = self.meta_size();
+        let mismatch =
+            || Error::new(ErrorKind::ChecksumMismatch).with_address(real_address + meta_size);
+
+        let mut meta = vec![0u8; meta_size as usize];
+        vol.read_exact_at(real_address, &mut meta)?;
+
+        let (block_size, _, checksum) = RSSBlock::parse_meta(&meta).ok_or_else(mismatch)?;
+
+        if block_size != size {
+            return Err(Error::new(ErrorKind::VolumeCorrupted)
+                .with_address(real_address + meta_size)
+                .with_sizes(size, block_size));
+        }
+
+        if self.checksums == ChecksumMode::Full {
+            let actual = payload_checksum(vol, real_address + meta_size, size - meta_size)?;
+
+            if checksum != Some(actual) {
+                return Err(
+                    mismatch().with_sizes(checksum.unwrap_or_default() as u64, actual as u64)
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     fn reset(&mut self) {
-        self.blocks.clear();
+        self.used_blocks.clear();
+        self.unused_blocks.clear();
+        self.unused_by_size.clear();
+        self.volume_size = 0;
         self.is_initialized = false;
+        self.map_address = None;
+        self.next_fit_address = 0;
+        self.payload_checksums.clear();
+    }
+
+    fn save_map(&mut self, vol: &mut B, generation: u32) -> Result<Option<BlockMapRef>> {
+        if !self.is_initialized {
+            return Err(ErrorKind::AllocatorNotInitialized.into());
+        }
+        debug_assert!(self.map_address.is_none());
+
+        // blok yang berisi peta juga ikut tercatat di dalam peta
+        let entries = self.used_blocks.len() as u64 + 1;
+        let len = MAP_HEADER_SIZE + entries * MAP_ENTRY_SIZE;
+        let address = self.alloc(vol, len as usize)?;
+
+        let mut bytes = Vec::with_capacity(len as usize);
+        bytes.extend_from_slice(&generation.to_be_bytes());
+        bytes.extend_from_slice(&[0u8; 4]);
+
+        for (address, size) in self.used_blocks.iter() {
+            bytes.extend_from_slice(&address.to_be_bytes());
+            bytes.extend_from_slice(&size.to_be_bytes());
+        }
+
+        debug_assert!(bytes.len() as u64 == len);
+        vol.write_all_at(address, &bytes)?;
+
+        if self.checksums == ChecksumMode::Full {
+            let real_address = address - self.meta_size();
+
+            self.refresh_checksum(real_address, vol)?;
+            self.mark_block(real_address, vol)?;
+        }
+
+        self.map_address = Some(address);
+        Ok(Some(BlockMapRef {
+            address,
+            entries,
+            checksum: crc32c::crc32c(&bytes),
+        }))
+    }
+
+    fn load_map(
+        &mut self,
+        vol: &mut B,
+        size: u64,
+        map: &BlockMapRef,
+        generation: u32,
+    ) -> Result<()> {
+        let corrupted = || Error::new(ErrorKind::VolumeCorrupted).with_address(map.address);
+
+        self.volume_size = size;
+
+        let len = map
+            .entries
+            .checked_mul(MAP_ENTRY_SIZE)
+            .and_then(|len| len.checked_add(MAP_HEADER_SIZE))
+            .filter(|len| map.address.checked_add(*len).is_some_and(|end| end <= size))
+            .ok_or_else(corrupted)?;
+
+        let mut bytes = vec![0u8; len as usize];
+        vol.read_exact_at(map.address, &mut bytes)?;
+
+        if crc32c::crc32c(&bytes) != map.checksum {
+            return Err(corrupted());
+        }
+
+        // peta yang utuh belum tentu berasal dari penyimpanan yang
+        // tercatat di superblock (misalnya superblock ditulis ulang oleh
+        // versi yang tidak mengenali peta blok)
+        if map_generation(&bytes) != generation {
+            return Err(corrupted());
+        }
+
+        init::load_blocks(&bytes[MAP_HEADER_SIZE as usize..], self)?;
+
+        // peta harus mencatat blok tempat peta itu sendiri berada
+        let meta_size = self.meta_size();
+        let map_block_size = self.used_blocks.get(&(map.address - meta_size));
+
+        if map_block_size != Some(&(len + meta_size)) {
+            return Err(corrupted());
+        }
+
+        // metadata dari tiap blok tetap harus diperiksa
+        if self.checksums != ChecksumMode::Disabled {
+            init::load_checksums(vol, self)?;
+        }
+
+        self.map_address = Some(map.address);
+        self.is_initialized = true;
+        Ok(())
     }
 }
@@ -0,0 +1,238 @@
+use super::*;
+use crate::fsck::Issue;
+
+/// Hasil penelusuran linked-list blok secara langsung dari volume, tanpa
+/// melalui `RSSAllocator`.
+pub struct Walk {
+    /// Blok-blok (termasuk head) yang dapat dijangkau sebelum node rusak
+    /// pertama, alamat -> ukuran.
+    pub blocks: BTreeMap<u64, u64>,
+
+    pub issues: Vec<Issue>,
+}
+
+/// Menelusuri seluruh blok mulai dari head hingga node rusak pertama (jika
+/// ada). Berbeda dengan `init::scan_blocks`, kerusakan yang ditemukan
+/// dicatat sebagai `Issue` alih-alih menghasilkan error.
+///
+/// Parameter size merupakan ukuran total dari volume, sebagaimana yang
+/// tercatat di superblock.
+pub fn walk_blocks<B: VolumeBackend>(
+    vol: &mut B,
+    size: u64,
+    checksums: ChecksumMode,
+) -> Result<Walk> {
+    let meta_size = RSSBlock::meta_size_of(checksums);
+
+    let mut walk = Walk {
+        blocks: BTreeMap::new(),
+        issues: vec![],
+    };
+
+    let mut address = NEONDB_FILE_ALLOCATABLE_START;
+    let mut previous_end = NEONDB_FILE_ALLOCATABLE_START;
+    let mut buff = [0u8; RSSBlock::CHECKSUMMED_META_SIZE as usize];
+
+    loop {
+        let index = walk.blocks.len();
+
+        if index > 0 && address == NULL_ADDRESS {
+            break;
+        }
+
+        // alamat blok selanjutnya selalu lebih besar dari blok sebelumnya,
+        // sehingga node yang sudah dilewati berarti terdapat siklus
+        if walk.blocks.contains_key(&address) {
+            walk.issues.push(Issue::Cycle { index, address });
+            break;
+        }
+
+        if address < previous_end {
+            walk.issues.push(Issue::Overlap {
+                index,
+                address,
+                previous_end,
+            });
+            break;
+        }
+
+        if address.checked_add(meta_size).is_none_or(|end| end > size) {
+            walk.issues.push(Issue::OutOfRange {
+                index,
+                address,
+                size: 0,
+            });
+            break;
+        }
+
+        let meta = &mut buff[..meta_size as usize];
+        vol.read_exact_at(address, meta)?;
+
+        let (block_size, next_address, checksum) = match RSSBlock::parse_meta(meta) {
+            Some(values) => values,
+            None => {
+                walk.issues.push(Issue::ChecksumMismatch { index, address });
+                break;
+            }
+        };
+
+        if index == 0 && block_size != meta_size {
+            walk.issues.push(Issue::BadHeadSize {
+                expected: meta_size,
+                actual: block_size,
+            });
+            break;
+        }
+
+        // blok tanpa isi (hasil dari alokasi 0 byte) tetap valid
+        let is_in_range = block_size >= meta_size
+            && address
+                .checked_add(block_size)
+                .is_some_and(|end| end <= size);
+
+        if !is_in_range {
+            walk.issues.push(Issue::OutOfRange {
+                index,
+                address,
+                size: block_size,
+            });
+            break;
+        }
+
+        // isi blok yang rusak tetap dapat diselamatkan, sehingga
+        // penelusuran tetap dilanjutkan
+        if checksums == ChecksumMode::Full {
+            let actual = payload_checksum(vol, address + meta_size, block_size - meta_size)?;
+
+            if checksum != Some(actual) {
+                walk.issues
+                    .push(Issue::PayloadChecksumMismatch { index, address });
+            }
+        }
+
+        walk.blocks.insert(address, block_size);
+
+        previous_end = address + block_size;
+        address = next_address;
+    }
+
+    Ok(walk)
+}
+
+/// Ukuran dari blok yang metadatanya berada di alamat yang diberikan,
+/// tanpa menelusuri linked-list. None jika metadata tersebut tidak valid
+/// ataupun blok tersebut melewati batas volume.
+pub fn read_block_size<B: VolumeBackend>(
+    vol: &mut B,
+    address: u64,
+    size: u64,
+    checksums: ChecksumMode,
+) -> Result<Option<u64>> {
+    let meta_size = RSSBlock::meta_size_of(checksums);
+
+    if address < NEONDB_FILE_ALLOCATABLE_START
+        || address.checked_add(meta_size).is_none_or(|end| end > size)
+    {
+        return Ok(None);
+    }
+
+    let mut buff = [0u8; RSSBlock::CHECKSUMMED_META_SIZE as usize];
+    let meta = &mut buff[..meta_size as usize];
+    vol.read_exact_at(address, meta)?;
+
+    let block_size = RSSBlock::parse_meta(meta).map(|(block_size, _, _)| block_size);
+
+    Ok(block_size.filter(|block_size| {
+        *block_size >= meta_size
+            && address
+                .checked_add(*block_size)
+                .is_some_and(|end| end <= size)
+    }))
+}
+
+/// Menulis ulang metadata dari blok-blok yang diberikan (alamat ->
+/// ukuran, dimana blok pertama harus merupakan head), sehingga linked-list
+/// hanya terdiri dari blok-blok tersebut.
+///
+/// Checksum dari isi tiap blok dihitung ulang, sehingga blok yang isinya
+/// rusak tetap dapat dibaca kembali. Jika tidak terdapat blok sama sekali
+/// (misalnya karena head rusak), head yang baru akan dibuat.
+pub fn relink_blocks<B: VolumeBackend>(
+    vol: &mut B,
+    blocks: &BTreeMap<u64, u64>,
+    checksums: ChecksumMode,
+) -> Result<()> {
+    let meta_size = RSSBlock::meta_size_of(checksums);
+    let head = BTreeMap::from([(NEONDB_FILE_ALLOCATABLE_START, meta_size)]);
+
+    let blocks = if blocks.is_empty() { &head } else { blocks };
+    debug_assert!(blocks.keys().next() == Some(&NEONDB_FILE_ALLOCATABLE_START));
+
+    let next_addresses = blocks.keys().skip(1).copied().chain([NULL_ADDRESS]);
+
+    for ((address, size), next_address) in blocks.iter().zip(next_addresses) {
+        let checksum = match checksums {
+            ChecksumMode::Disabled => None,
+            ChecksumMode::Metadata => Some(0),
+            ChecksumMode::Full => Some(payload_checksum(
+                vol,
+                address + meta_size,
+                size - meta_size,
+            )?),
+        };
+
+        let block = RSSBlock {
+            address: *address,
+            size: *size,
+        };
+        vol.write_all_at(*address, &block.construct_meta(next_address, checksum))?;
+    }
+
+    Ok(())
+}
+
+/// Ukuran metadata dari tiap blok, sesuai dengan cakupan checksum dari
+/// volume.
+pub fn meta_size_of(checksums: ChecksumMode) -> u64 {
+    RSSBlock::meta_size_of(checksums)
+}
+
+/// Membaca entri-entri peta blok (alamat -> ukuran) yang tercatat di
+/// superblock. None jika peta tersebut tidak valid, termasuk jika
+/// generasinya berbeda dengan generasi yang diberikan.
+pub fn read_block_map<B: VolumeBackend>(
+    vol: &mut B,
+    size: u64,
+    map: &BlockMapRef,
+    generation: u32,
+) -> Result<Option<BTreeMap<u64, u64>>> {
+    let len = map
+        .entries
+        .checked_mul(MAP_ENTRY_SIZE)
+        .and_then(|len| len.checked_add(MAP_HEADER_SIZE))
+        .filter(|len| map.address.checked_add(*len).is_some_and(|end| end <= size));
+
+    let len = match len {
+        Some(len) => len,
+        None => return Ok(None),
+    };
+
+    let mut bytes = vec![0u8; len as usize];
+    vol.read_exact_at(map.address, &mut bytes)?;
+
+    if crc32c::crc32c(&bytes) != map.checksum || map_generation(&bytes) != generation {
+        return Ok(None);
+    }
+
+    let entries = bytes[MAP_HEADER_SIZE as usize..]
+        .chunks(MAP_ENTRY_SIZE as usize)
+        .map(|entry| {
+            (
+                u64::from_be_bytes(entry[..8].try_into().unwrap()),
+                u64::from_be_bytes(entry[8..].try_into().unwrap()),
+            )
+        })
+        .collect();
+
+    Ok(Some(entries))
+}
@@ -1,50 +1,67 @@
 use super::*;
 
-pub fn obtain_head(vol: &mut File, allocator: &mut RSSAllocator) -> Result<u64> {
+pub fn obtain_head<B: VolumeBackend>(vol: &mut B, allocator: &mut RSSAllocator) -> Result<u64> {
     let address = NEONDB_FILE_ALLOCATABLE_START;
-    let mut buff = [0u8; 16];
+    let (size, next_address, checksum) = read_meta(vol, address, 0, allocator)?;
 
-    Ops::read(address, &mut buff, vol);
-
-    let (size, next_address) = extract_values(&buff[..]);
-
-    if size != RSSBlock::META_SIZE {
-        return Err(ErrorKind::VolumeCorrupted);
+    if size != allocator.meta_size() {
+        return Err(Error::new(ErrorKind::VolumeCorrupted)
+            .with_address(address)
+            .with_index(0)
+            .with_sizes(allocator.meta_size(), size));
     }
 
-    push_block(address, size, allocator);
+    push_block(address, size, checksum, allocator);
     Ok(next_address)
 }
 
-pub fn new_volume(vol: &mut File, allocator: &mut RSSAllocator) -> Result<()> {
-    debug_assert!(allocator.blocks.len() == 0);
+pub fn new_volume<B: VolumeBackend>(vol: &mut B, allocator: &mut RSSAllocator) -> Result<()> {
+    debug_assert!(allocator.used_blocks.is_empty());
 
-    // Menambahkan head
+    // Menambahkan head, yang isinya kosong (checksum-nya bernilai 0)
     push_block(
         NEONDB_FILE_ALLOCATABLE_START,
-        RSSBlock::META_SIZE,
+        allocator.meta_size(),
+        Some(0),
         allocator,
     );
-    allocator.mark_block(0, vol);
+    allocator.mark_block(NEONDB_FILE_ALLOCATABLE_START, vol)?;
 
     push_remaining_space(allocator)?;
 
     Ok(())
 }
 
-pub fn scan_blocks(vol: &mut File, start_address: u64, allocator: &mut RSSAllocator) -> Result<()> {
+pub fn scan_blocks<B: VolumeBackend>(
+    vol: &mut B,
+    start_address: u64,
+    allocator: &mut RSSAllocator,
+) -> Result<()> {
     let mut address = start_address;
-    let mut buff = [0u8; 16];
 
     while address != NULL_ADDRESS {
-        Ops::read(address, &mut buff, vol);
-
-        let (size, next_address) = extract_values(&buff[..]);
+        let index = allocator.used_blocks.len();
+        let (size, next_address, checksum) = read_meta(vol, address, index, allocator)?;
+
+        // Sama seperti `check::walk_blocks`, alamat blok berikutnya harus
+        // selalu lebih besar, sehingga rantai blok yang rusak (misalnya
+        // membentuk siklus) tidak akan ditelusuri tanpa henti.
+        let is_valid = size >= allocator.meta_size()
+            && address
+                .checked_add(size)
+                .is_some_and(|end| end <= allocator.volume_size)
+            && (next_address == NULL_ADDRESS || next_address > address);
+
+        if !is_valid {
+            return Err(Error::new(ErrorKind::VolumeCorrupted)
+                .with_address(address)
+                .with_index(index));
+        }
 
         if gap_exist_before(address, allocator)? {
             push_unused_block_before(address, allocator);
         }
-        push_block(address, size, allocator);
+        push_block(address, size, checksum, allocator);
 
         address = next_address;
     }
@@ -54,28 +71,102 @@ pub fn scan_blocks(vol: &mut File, start_address: u64, allocator: &mut RSSAlloca
     Ok(())
 }
 
-fn push_block(address: u64, size: u64, allocator: &mut RSSAllocator) {
-    allocator.blocks.push(RSSBlock {
-        address,
-        size,
-        is_used: true,
-    });
+// Membangun ulang daftar blok dari entri-entri peta blok, tanpa perlu
+// membaca metadata dari tiap blok di volume.
+pub fn load_blocks(bytes: &[u8], allocator: &mut RSSAllocator) -> Result<()> {
+    debug_assert!(allocator.used_blocks.is_empty());
+
+    for (i, entry) in bytes.chunks(MAP_ENTRY_SIZE as usize).enumerate() {
+        let (address, size) = extract_values(entry);
+
+        let is_valid = match i {
+            0 => address == NEONDB_FILE_ALLOCATABLE_START && size == allocator.meta_size(),
+            _ => size >= allocator.meta_size(),
+        };
+
+        if !is_valid {
+            return Err(Error::new(ErrorKind::VolumeCorrupted)
+                .with_address(address)
+                .with_index(i));
+        }
+
+        if i > 0 && gap_exist_before(address, allocator)? {
+            push_unused_block_before(address, allocator);
+        }
+        push_block(address, size, None, allocator);
+    }
+
+    if allocator.used_blocks.is_empty() {
+        return Err(ErrorKind::VolumeCorrupted.into());
+    }
+
+    push_remaining_space(allocator)
 }
 
-fn push_unused_block_before(next_block_address: u64, allocator: &mut RSSAllocator) {
-    debug_assert!(allocator.blocks.last().unwrap().is_used);
+// Memeriksa metadata dari seluruh blok yang dimuat dari peta blok, sekaligus
+// memuat checksum dari isi tiap blok (jika ada).
+pub fn load_checksums<B: VolumeBackend>(vol: &mut B, allocator: &mut RSSAllocator) -> Result<()> {
+    let blocks = allocator
+        .used_blocks
+        .iter()
+        .map(|(address, size)| (*address, *size))
+        .collect::<Vec<_>>();
+
+    for (i, (address, size)) in blocks.into_iter().enumerate() {
+        let (block_size, _, checksum) = read_meta(vol, address, i, allocator)?;
+
+        if block_size != size {
+            return Err(Error::new(ErrorKind::VolumeCorrupted)
+                .with_address(address)
+                .with_index(i)
+                .with_sizes(size, block_size));
+        }
+
+        push_block(address, size, checksum, allocator);
+    }
+
+    Ok(())
+}
+
+// Membaca metadata dari blok ke-index yang berada pada alamat yang
+// diberikan, sekaligus memeriksa checksum-nya (jika ada).
+fn read_meta<B: VolumeBackend>(
+    vol: &mut B,
+    address: u64,
+    index: usize,
+    allocator: &RSSAllocator,
+) -> Result<(u64, u64, Option<u32>)> {
+    let meta_size = allocator.meta_size();
+
+    let mut buff = [0u8; RSSBlock::CHECKSUMMED_META_SIZE as usize];
+    let buff = &mut buff[..meta_size as usize];
+
+    Ops::read(address, buff, vol)?;
+
+    RSSBlock::parse_meta(buff).ok_or_else(|| {
+        Error::new(ErrorKind::ChecksumMismatch)
+            .with_address(address + meta_size)
+            .with_index(index)
+    })
+}
 
+fn push_block(address: u64, size: u64, checksum: Option<u32>, allocator: &mut RSSAllocator) {
+    allocator.used_blocks.insert(address, size);
+
+    if let (ChecksumMode::Full, Some(checksum)) = (allocator.checksums, checksum) {
+        allocator.payload_checksums.insert(address, checksum);
+    }
+}
+
+fn push_unused_block_before(next_block_address: u64, allocator: &mut RSSAllocator) {
     let address = next_push_address(allocator);
-    allocator.blocks.push(RSSBlock {
-        address,
-        size: next_block_address - address,
-        is_used: false,
-    });
+
+    allocator.insert_unused_block(address, next_block_address - address);
 }
 
 fn push_remaining_space(allocator: &mut RSSAllocator) -> Result<()> {
-    // alamat byte terakhir yang dapat ditempati oleh data
-    let last_address = NEONDB_FILE_ALLOCATABLE_SIZE;
+    // alamat tepat setelah byte terakhir yang dapat ditempati oleh data
+    let last_address = allocator.volume_size;
 
     if gap_exist_before(last_address, allocator)? {
         push_unused_block_before(last_address, allocator);
@@ -85,20 +176,21 @@ fn push_remaining_space(allocator: &mut RSSAllocator) -> Result<()> {
 }
 
 fn gap_exist_before(next_block_address: u64, allocator: &RSSAllocator) -> Result<bool> {
-    debug_assert!(allocator.blocks.last().unwrap().is_used);
-
     let address = next_push_address(allocator);
+    // blok tumpang tindih dengan blok sebelumnya
     if address > next_block_address {
-        return Err(ErrorKind::VolumeCorrupted);
+        return Err(Error::new(ErrorKind::VolumeCorrupted)
+            .with_address(next_block_address)
+            .with_index(allocator.used_blocks.len()));
     }
 
     Ok(address < next_block_address)
 }
 
-// Address awal dari blok yang akan dipush berikutnya
+// Address awal dari blok yang akan dipush berikutnya, yaitu tepat
+// setelah blok terpakai yang terakhir
 fn next_push_address(allocator: &RSSAllocator) -> u64 {
-    let address = allocator.blocks.last().unwrap().address;
-    let size = allocator.blocks.last().unwrap().size;
+    let (address, size) = allocator.used_blocks.iter().next_back().unwrap();
 
     address + size
 }
@@ -1,8 +1,11 @@
+use crate::ChecksumMode;
+
+use std::convert::TryInto;
+
 #[derive(Debug)]
 pub struct RSSBlock {
     pub address: u64,
     pub size: u64,
-    pub is_used: bool,
 }
 
 impl RSSBlock {
@@ -13,12 +16,60 @@ impl RSSBlock {
     // 8 byte sisanya untuk alamat blok selanjutnya
     pub const META_SIZE: u64 = 16;
 
-    pub fn construct_meta(&self, next_block_address: u64) -> Vec<u8> {
-        self.size
+    // Pada volume yang menggunakan checksum, BLOCK_META diikuti oleh
+    // 4 byte checksum dari metadata (mencakup seluruh byte metadata
+    // lainnya), dan 4 byte checksum dari isi blok (0 jika tidak
+    // digunakan).
+    pub const CHECKSUMMED_META_SIZE: u64 = 24;
+
+    pub fn meta_size_of(mode: ChecksumMode) -> u64 {
+        match mode {
+            ChecksumMode::Disabled => RSSBlock::META_SIZE,
+            _ => RSSBlock::CHECKSUMMED_META_SIZE,
+        }
+    }
+
+    // Checksum dari isi blok hanya disertakan jika volume menggunakan
+    // checksum, dan menentukan layout dari metadata yang dihasilkan.
+    pub fn construct_meta(&self, next_block_address: u64, checksum: Option<u32>) -> Vec<u8> {
+        let mut meta = self
+            .size
             .to_be_bytes()
             .iter()
             .chain(&next_block_address.to_be_bytes())
-            .map(|b| *b)
-            .collect::<Vec<u8>>()
+            .copied()
+            .collect::<Vec<u8>>();
+
+        if let Some(checksum) = checksum {
+            let checksum = checksum.to_be_bytes();
+            let meta_checksum = crc32c::crc32c_append(crc32c::crc32c(&meta), &checksum);
+
+            meta.extend_from_slice(&meta_checksum.to_be_bytes());
+            meta.extend_from_slice(&checksum);
+        }
+
+        meta
+    }
+
+    // Kebalikan dari `construct_meta`, yaitu ukuran blok, alamat blok
+    // selanjutnya, dan checksum dari isi blok (jika ada). None jika
+    // checksum dari metadata tidak sesuai.
+    pub fn parse_meta(bytes: &[u8]) -> Option<(u64, u64, Option<u32>)> {
+        let size = u64::from_be_bytes(bytes[..8].try_into().unwrap());
+        let next_block_address = u64::from_be_bytes(bytes[8..16].try_into().unwrap());
+
+        if bytes.len() as u64 == RSSBlock::META_SIZE {
+            return Some((size, next_block_address, None));
+        }
+
+        let meta_checksum = u32::from_be_bytes(bytes[16..20].try_into().unwrap());
+        let checksum = &bytes[20..24];
+
+        if crc32c::crc32c_append(crc32c::crc32c(&bytes[..16]), checksum) != meta_checksum {
+            return None;
+        }
+
+        let checksum = u32::from_be_bytes(checksum.try_into().unwrap());
+        Some((size, next_block_address, Some(checksum)))
     }
 }
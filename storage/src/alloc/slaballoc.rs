@@ -0,0 +1,433 @@
+use super::*;
+use crate::ops::Ops;
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryInto;
+
+const NULL_ADDRESS: u64 = 0;
+
+/// Slab allocator untuk objek-objek berukuran kecil.
+///
+/// Alih-alih dialokasikan sebagai blok tersendiri (beserta metadata
+/// milik allocator), objek-objek dengan ukuran yang sama dikumpulkan ke
+/// dalam halaman-halaman slab. Tiap halaman slab merupakan sebuah blok
+/// biasa yang dialokasikan melalui `Allocator`, dan memiliki bitmap yang
+/// menandai slot-slot yang sedang digunakan.
+///
+/// Seluruh halaman slab saling terhubung (berdasarkan urutan alamat),
+/// dimana alamat halaman pertama dicatat di superblock.
+///
+/// Layout header dari tiap halaman (big-endian):
+///
+/// - 8 byte alamat halaman selanjutnya
+/// - 4 byte ukuran objek
+/// - 4 byte cadangan
+/// - 64 byte bitmap slot
+pub struct SlabAllocator {
+    pages: BTreeMap<u64, SlabPage>,
+
+    // halaman-halaman yang masih memiliki slot kosong, per ukuran objek
+    partial_pages: BTreeMap<u64, BTreeSet<u64>>,
+
+    // halaman-halaman yang isinya diubah sejak `take_dirty_pages`
+    // dipanggil terakhir kali
+    dirty_pages: BTreeSet<u64>,
+}
+
+struct SlabPage {
+    object_size: u64,
+    bitmap: [u64; SlabAllocator::BITMAP_WORDS],
+    used: u64,
+}
+
+impl SlabPage {
+    fn capacity(&self) -> u64 {
+        SlabAllocator::capacity_of(self.object_size)
+    }
+
+    fn is_full(&self) -> bool {
+        self.used == self.capacity()
+    }
+
+    fn is_used(&self, slot: u64) -> bool {
+        self.bitmap[(slot / 64) as usize] & (1 << (slot % 64)) != 0
+    }
+
+    fn toggle(&mut self, slot: u64) {
+        self.bitmap[(slot / 64) as usize] ^= 1 << (slot % 64);
+    }
+
+    fn first_unused_slot(&self) -> Option<u64> {
+        (0..self.capacity()).find(|slot| !self.is_used(*slot))
+    }
+}
+
+impl SlabAllocator {
+    // Ukuran data dari blok yang menjadi halaman slab. Dengan metadata
+    // milik allocator (tanpa checksum), blok tersebut tepat menempati
+    // 4 KiB.
+    pub const PAGE_SIZE: u64 = 4080;
+
+    // Ukuran objek terbesar yang dapat ditampung oleh halaman slab
+    pub const MAX_OBJECT_SIZE: u64 = 256;
+
+    // Ukuran objek selalu dibulatkan ke kelipatan dari angka ini
+    const OBJECT_ALIGN: u64 = 8;
+
+    const BITMAP_WORDS: usize = 8;
+    const HEADER_SIZE: u64 = 16 + SlabAllocator::BITMAP_WORDS as u64 * 8;
+
+    pub fn new() -> SlabAllocator {
+        SlabAllocator {
+            pages: BTreeMap::new(),
+            partial_pages: BTreeMap::new(),
+            dirty_pages: BTreeSet::new(),
+        }
+    }
+
+    pub fn object_size_of(size: u64) -> u64 {
+        let size = size.max(1);
+
+        size.div_ceil(SlabAllocator::OBJECT_ALIGN) * SlabAllocator::OBJECT_ALIGN
+    }
+
+    fn capacity_of(object_size: u64) -> u64 {
+        (SlabAllocator::PAGE_SIZE - SlabAllocator::HEADER_SIZE) / object_size
+    }
+
+    /// Alamat dari halaman slab pertama, yang dicatat di superblock.
+    pub fn root(&self) -> Option<u64> {
+        self.pages.keys().next().copied()
+    }
+
+    /// Apakah alamat yang diberikan terletak di dalam halaman slab.
+    pub fn owns(&self, address: u64) -> bool {
+        self.page_of(address).is_some()
+    }
+
+    fn page_of(&self, address: u64) -> Option<u64> {
+        self.pages
+            .range(..=address)
+            .next_back()
+            .map(|(page, _)| *page)
+            .filter(|page| address < page + SlabAllocator::PAGE_SIZE)
+    }
+
+    // Memuat seluruh halaman slab, mulai dari halaman dengan alamat root.
+    // Tiap halaman harus merupakan blok yang tercatat di allocator.
+    pub fn init<B: VolumeBackend>(
+        &mut self,
+        vol: &mut B,
+        root: Option<u64>,
+        blocks: &[Block],
+    ) -> Result<()> {
+        debug_assert!(self.pages.is_empty());
+
+        let mut address = root.unwrap_or(NULL_ADDRESS);
+        let mut buff = [0u8; SlabAllocator::HEADER_SIZE as usize];
+
+        while address != NULL_ADDRESS {
+            let corrupted = || {
+                Error::new(ErrorKind::VolumeCorrupted)
+                    .with_address(address)
+                    .with_index(self.pages.len())
+            };
+
+            // halaman harus berurutan, sekaligus mencegah siklus
+            let is_ordered = self
+                .pages
+                .keys()
+                .next_back()
+                .is_none_or(|last| *last < address);
+
+            let is_block = blocks
+                .binary_search_by_key(&address, |b| b.address)
+                .is_ok_and(|i| blocks[i].size >= SlabAllocator::PAGE_SIZE);
+
+            if !is_ordered || !is_block {
+                return Err(corrupted());
+            }
+
+            Ops::read(address, &mut buff, vol)?;
+
+            let next_address = u64::from_be_bytes(buff[..8].try_into().unwrap());
+            let object_size = u32::from_be_bytes(buff[8..12].try_into().unwrap()) as u64;
+
+            let mut page = SlabPage {
+                object_size,
+                bitmap: [0; SlabAllocator::BITMAP_WORDS],
+                used: 0,
+            };
+
+            for (i, word) in buff[16..].chunks(8).enumerate() {
+                page.bitmap[i] = u64::from_be_bytes(word.try_into().unwrap());
+            }
+
+            let is_valid = object_size > 0
+                && object_size <= SlabAllocator::MAX_OBJECT_SIZE
+                && object_size.is_multiple_of(SlabAllocator::OBJECT_ALIGN)
+                && (page.capacity()..64 * SlabAllocator::BITMAP_WORDS as u64)
+                    .all(|slot| !page.is_used(slot));
+
+            page.used = page.bitmap.iter().map(|w| w.count_ones() as u64).sum();
+
+            // halaman yang kosong seharusnya sudah didealokasi
+            if !is_valid || page.used == 0 {
+                return Err(corrupted());
+            }
+
+            if !page.is_full() {
+                self.partial_pages
+                    .entry(object_size)
+                    .or_default()
+                    .insert(address);
+            }
+            self.pages.insert(address, page);
+
+            address = next_address;
+        }
+
+        Ok(())
+    }
+
+    /// Mengalokasikan sebuah objek di halaman slab yang masih memiliki
+    /// slot kosong. None jika tidak terdapat halaman yang sesuai, dimana
+    /// halaman baru harus ditambahkan terlebih dulu dengan `add_page`.
+    pub fn alloc<B: VolumeBackend>(&mut self, vol: &mut B, size: u64) -> Result<Option<u64>> {
+        let object_size = SlabAllocator::object_size_of(size);
+
+        let address = match self
+            .partial_pages
+            .get(&object_size)
+            .and_then(|pages| pages.iter().next())
+        {
+            Some(address) => *address,
+            None => return Ok(None),
+        };
+
+        let page = self.pages.get_mut(&address).unwrap();
+        let slot = page.first_unused_slot().unwrap();
+
+        page.toggle(slot);
+        page.used += 1;
+
+        if page.is_full() {
+            self.partial_pages
+                .get_mut(&object_size)
+                .unwrap()
+                .remove(&address);
+        }
+
+        self.write_bitmap(address, vol)?;
+
+        Ok(Some(
+            address + SlabAllocator::HEADER_SIZE + slot * object_size,
+        ))
+    }
+
+    /// Menjadikan blok (berukuran `PAGE_SIZE`) pada alamat yang diberikan
+    /// sebagai halaman slab baru untuk objek dengan ukuran size.
+    pub fn add_page<B: VolumeBackend>(
+        &mut self,
+        vol: &mut B,
+        address: u64,
+        size: u64,
+    ) -> Result<()> {
+        let object_size = SlabAllocator::object_size_of(size);
+        debug_assert!(object_size <= SlabAllocator::MAX_OBJECT_SIZE);
+
+        let next_address = self.next_page_after(address);
+
+        let mut header = Vec::with_capacity(SlabAllocator::HEADER_SIZE as usize);
+        header.extend_from_slice(&next_address.to_be_bytes());
+        header.extend_from_slice(&(object_size as u32).to_be_bytes());
+        header.resize(SlabAllocator::HEADER_SIZE as usize, 0);
+
+        vol.write_all_at(address, &header)?;
+        self.link_page_before(address, address, vol)?;
+        self.dirty_pages.insert(address);
+
+        self.pages.insert(
+            address,
+            SlabPage {
+                object_size,
+                bitmap: [0; SlabAllocator::BITMAP_WORDS],
+                used: 0,
+            },
+        );
+        self.partial_pages
+            .entry(object_size)
+            .or_default()
+            .insert(address);
+
+        Ok(())
+    }
+
+    /// Membebaskan objek pada alamat yang diberikan. Jika halaman tempat
+    /// objek tersebut berada menjadi kosong, halaman tersebut akan dilepas
+    /// dan alamatnya dikembalikan agar dapat didealokasi.
+    pub fn dealloc<B: VolumeBackend>(&mut self, vol: &mut B, address: u64) -> Result<Option<u64>> {
+        let not_found = || Error::new(ErrorKind::BlockNotFound).with_address(address);
+
+        let page_address = self.page_of(address).ok_or_else(not_found)?;
+        let page = self.pages.get_mut(&page_address).unwrap();
+
+        let offset = address
+            .checked_sub(page_address + SlabAllocator::HEADER_SIZE)
+            .filter(|offset| offset.is_multiple_of(page.object_size))
+            .ok_or_else(not_found)?;
+
+        let slot = offset / page.object_size;
+        if slot >= page.capacity() || !page.is_used(slot) {
+            return Err(not_found());
+        }
+
+        page.toggle(slot);
+        page.used -= 1;
+
+        let object_size = page.object_size;
+        let is_empty = page.used == 0;
+
+        let partial_pages = self.partial_pages.entry(object_size).or_default();
+
+        if is_empty {
+            partial_pages.remove(&page_address);
+            self.pages.remove(&page_address);
+
+            let next_address = self.next_page_after(page_address);
+            self.link_page_before(page_address, next_address, vol)?;
+
+            return Ok(Some(page_address));
+        }
+
+        partial_pages.insert(page_address);
+        self.write_bitmap(page_address, vol)?;
+
+        Ok(None)
+    }
+
+    /// Menggantikan blok-blok yang menjadi halaman slab dengan objek-objek
+    /// yang sedang digunakan di dalamnya.
+    pub fn blocks(&self, blocks: Vec<Block>) -> Vec<Block> {
+        let mut result = Vec::with_capacity(blocks.len());
+
+        for block in blocks {
+            let page = match self.pages.get(&block.address) {
+                Some(page) => page,
+                None => {
+                    result.push(block);
+                    continue;
+                }
+            };
+
+            for slot in (0..page.capacity()).filter(|slot| page.is_used(*slot)) {
+                result.push(Block {
+                    address: block.address + SlabAllocator::HEADER_SIZE + slot * page.object_size,
+                    size: page.object_size,
+                });
+            }
+        }
+
+        result
+    }
+
+    /// Menyesuaikan alamat dari halaman-halaman slab setelah blok-blok
+    /// dipindahkan oleh allocator, dan mengembalikan alamat lama -> alamat
+    /// baru dari objek-objek di dalam halaman yang dipindahkan.
+    pub fn relocate<B: VolumeBackend>(
+        &mut self,
+        vol: &mut B,
+        remap: &BTreeMap<u64, u64>,
+    ) -> Result<BTreeMap<u64, u64>> {
+        let mut objects = BTreeMap::new();
+
+        if !self.pages.keys().any(|page| remap.contains_key(page)) {
+            return Ok(objects);
+        }
+
+        let pages = std::mem::take(&mut self.pages);
+        self.partial_pages.clear();
+
+        for (address, page) in pages {
+            let new_address = remap.get(&address).copied().unwrap_or(address);
+
+            if new_address != address {
+                for slot in (0..page.capacity()).filter(|slot| page.is_used(*slot)) {
+                    let offset = SlabAllocator::HEADER_SIZE + slot * page.object_size;
+                    objects.insert(address + offset, new_address + offset);
+                }
+            }
+
+            if !page.is_full() {
+                self.partial_pages
+                    .entry(page.object_size)
+                    .or_default()
+                    .insert(new_address);
+            }
+            self.pages.insert(new_address, page);
+        }
+
+        // urutan halaman tidak berubah, namun alamat halaman selanjutnya
+        // harus ditulis ulang di tiap halaman
+        for address in self.pages.keys() {
+            let next_address = self.next_page_after(*address);
+            vol.write_all_at(*address, &next_address.to_be_bytes())?;
+        }
+        self.dirty_pages.extend(self.pages.keys());
+
+        Ok(objects)
+    }
+
+    /// Mengambil (sekaligus mengosongkan) daftar halaman yang isinya
+    /// diubah, misalnya agar checksum dari blok-blok tersebut dapat
+    /// dihitung ulang. Halaman yang sudah dilepas tidak ikut disertakan.
+    pub fn take_dirty_pages(&mut self) -> BTreeSet<u64> {
+        let mut pages = std::mem::take(&mut self.dirty_pages);
+        pages.retain(|page| self.pages.contains_key(page));
+
+        pages
+    }
+
+    pub fn reset(&mut self) {
+        self.pages.clear();
+        self.partial_pages.clear();
+        self.dirty_pages.clear();
+    }
+
+    fn next_page_after(&self, address: u64) -> u64 {
+        self.pages
+            .range(address + 1..)
+            .next()
+            .map(|(next, _)| *next)
+            .unwrap_or(NULL_ADDRESS)
+    }
+
+    // Mengarahkan halaman sebelum address (jika ada) ke next_address.
+    // Jika tidak ada, halaman pertama cukup dicatat ulang di superblock.
+    fn link_page_before<B: VolumeBackend>(
+        &mut self,
+        address: u64,
+        next_address: u64,
+        vol: &mut B,
+    ) -> Result<()> {
+        if let Some((prev, _)) = self.pages.range(..address).next_back() {
+            vol.write_all_at(*prev, &next_address.to_be_bytes())?;
+            self.dirty_pages.insert(*prev);
+        }
+        Ok(())
+    }
+
+    fn write_bitmap<B: VolumeBackend>(&mut self, address: u64, vol: &mut B) -> Result<()> {
+        let page = &self.pages[&address];
+
+        let mut bytes = Vec::with_capacity(SlabAllocator::BITMAP_WORDS * 8);
+        for word in page.bitmap.iter() {
+            bytes.extend_from_slice(&word.to_be_bytes());
+        }
+
+        vol.write_all_at(address + 16, &bytes)?;
+        self.dirty_pages.insert(address);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use std::cmp;
+use std::convert::TryInto;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+pub use file::FileBackend;
+pub use memory::MemoryBackend;
+pub use mmap::MmapBackend;
+
+mod file;
+mod memory;
+mod mmap;
+
+/// Media penyimpanan yang menjadi tempat sebuah volume berada.
+///
+/// Seluruh akses terhadap volume (baik oleh `Storage` maupun oleh
+/// allocator) dilakukan melalui trait ini, sehingga volume tidak harus
+/// berupa file di filesystem.
+pub trait VolumeBackend {
+    /// Membaca byte mulai dari offset tertentu ke dalam buff, dan
+    /// mengembalikan jumlah byte yang berhasil dibaca.
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize>;
+
+    /// Menulis byte dari buff mulai dari offset tertentu, dan
+    /// mengembalikan jumlah byte yang berhasil ditulis.
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize>;
+
+    fn len(&self) -> io::Result<u64>;
+    fn set_len(&mut self, len: u64) -> io::Result<()>;
+
+    /// Menyerahkan penulisan yang masih ditahan oleh backend ke sistem
+    /// operasi, tanpa menunggu penulisan tersebut tersimpan secara
+    /// permanen.
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Memastikan seluruh penulisan sebelumnya sudah tersimpan secara
+    /// permanen di media penyimpanan.
+    fn sync(&mut self) -> io::Result<()>;
+
+    fn is_empty(&self) -> io::Result<bool> {
+        self.len().map(|len| len == 0)
+    }
+
+    fn read_exact_at(&mut self, mut offset: u64, mut buff: &mut [u8]) -> io::Result<()> {
+        while !buff.is_empty() {
+            match self.read_at(offset, buff)? {
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                n => {
+                    buff = &mut buff[n..];
+                    offset += n as u64;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_all_at(&mut self, mut offset: u64, mut buff: &[u8]) -> io::Result<()> {
+        while !buff.is_empty() {
+            match self.write_at(offset, buff)? {
+                0 => return Err(io::ErrorKind::WriteZero.into()),
+                n => {
+                    buff = &buff[n..];
+                    offset += n as u64;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Backend yang dapat dibuka (maupun dibuat) dari sebuah path di
+/// filesystem, sehingga dapat digunakan oleh `Storage::mount` dan
+/// `Storage::mount_new`.
+pub trait PathBackend: VolumeBackend + Sized {
+    fn open(path: &Path) -> io::Result<Self>;
+
+    // Error jika file dengan path yang diberikan sudah ada
+    fn create_new(path: &Path) -> io::Result<Self>;
+}
+
+// Membaca isi dari bytes layaknya membaca dari file, dimana pembacaan yang
+// melewati ukuran bytes hanya menghasilkan byte-byte yang tersedia.
+fn read_bytes_at(bytes: &[u8], offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+    let start = cmp::min(to_usize(offset)?, bytes.len());
+    let len = cmp::min(buff.len(), bytes.len() - start);
+
+    buff[..len].copy_from_slice(&bytes[start..start + len]);
+    Ok(len)
+}
+
+// Rentang byte yang ditulis oleh write_at dari backend yang berada di
+// memori. Sama seperti file, penulisan melewati ukuran backend akan
+// memperbesar ukuran dari backend tersebut hingga ujung dari rentang ini.
+fn write_range(offset: u64, len: usize) -> io::Result<Range<usize>> {
+    let start = to_usize(offset)?;
+    let end = start.checked_add(len).ok_or_else(out_of_range)?;
+
+    Ok(start..end)
+}
+
+fn to_usize(value: u64) -> io::Result<usize> {
+    value.try_into().map_err(|_| out_of_range())
+}
+
+fn out_of_range() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, "offset out of range")
+}
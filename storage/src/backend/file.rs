@@ -0,0 +1,58 @@
+use super::{PathBackend, VolumeBackend};
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, prelude::*, SeekFrom};
+use std::path::Path;
+
+/// Backend berupa file biasa di filesystem.
+pub struct FileBackend {
+    file: File,
+}
+
+impl From<File> for FileBackend {
+    fn from(file: File) -> FileBackend {
+        FileBackend { file }
+    }
+}
+
+impl PathBackend for FileBackend {
+    fn open(path: &Path) -> io::Result<FileBackend> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        Ok(FileBackend { file })
+    }
+
+    fn create_new(path: &Path) -> io::Result<FileBackend> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+
+        Ok(FileBackend { file })
+    }
+}
+
+impl VolumeBackend for FileBackend {
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.read(buff)
+    }
+
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write(buff)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        self.file.metadata().map(|m| m.len())
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.file.set_len(len)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.file.sync_data()
+    }
+}
@@ -0,0 +1,55 @@
+use super::VolumeBackend;
+
+use std::io;
+
+/// Backend yang seluruh isinya berada di memori.
+///
+/// Isi dari backend ini akan hilang begitu instance-nya di-drop.
+#[derive(Debug, Default)]
+pub struct MemoryBackend {
+    bytes: Vec<u8>,
+}
+
+impl MemoryBackend {
+    pub fn new() -> MemoryBackend {
+        MemoryBackend { bytes: Vec::new() }
+    }
+
+    pub fn from_bytes(bytes: Vec<u8>) -> MemoryBackend {
+        MemoryBackend { bytes }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl VolumeBackend for MemoryBackend {
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+        super::read_bytes_at(&self.bytes, offset, buff)
+    }
+
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize> {
+        let range = super::write_range(offset, buff.len())?;
+
+        if range.end > self.bytes.len() {
+            self.bytes.resize(range.end, 0);
+        }
+
+        self.bytes[range].copy_from_slice(buff);
+        Ok(buff.len())
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.bytes.len() as u64)
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.bytes.resize(super::to_usize(len)?, 0);
+        Ok(())
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
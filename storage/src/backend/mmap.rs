@@ -0,0 +1,121 @@
+use super::{PathBackend, VolumeBackend};
+
+use memmap2::MmapMut;
+
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::path::Path;
+
+/// Backend berupa file yang dipetakan ke memori (memory-mapped).
+///
+/// Operasi read dan write hanya berupa penyalinan memori, tanpa system
+/// call. Akibatnya, perubahan belum tentu tersimpan di file sebelum
+/// `flush` (atau `VolumeBackend::sync`) dipanggil.
+///
+/// Selama backend ini digunakan, file tidak boleh dipotong (truncate)
+/// maupun diubah oleh pihak lain, baik oleh proses lain maupun melalui
+/// file handle lain di proses yang sama. Pembacaan terhadap bagian file
+/// yang sudah dipotong akan menghentikan proses (SIGBUS), dan hal ini
+/// tidak dapat dicegah oleh backend ini. Lock yang dipegang oleh
+/// `Storage::mount` hanya bersifat advisory, sehingga hanya melindungi
+/// volume dari pengguna lain yang juga menggunakan lock tersebut.
+pub struct MmapBackend {
+    file: File,
+
+    // Pemetaan dengan panjang 0 tidak dapat dibuat, sehingga bernilai
+    // None selama file masih kosong.
+    map: Option<MmapMut>,
+}
+
+impl MmapBackend {
+    fn from_file(file: File) -> io::Result<MmapBackend> {
+        let mut backend = MmapBackend { file, map: None };
+        backend.remap()?;
+
+        Ok(backend)
+    }
+
+    fn remap(&mut self) -> io::Result<()> {
+        self.map = None;
+
+        if self.file.metadata()?.len() > 0 {
+            // SAFETY: pemetaan hanya valid selama ukuran maupun isi file
+            // tidak diubah di luar pemetaan ini. Di dalam backend ini,
+            // ukuran file hanya diubah melalui set_len yang selalu membuat
+            // ulang pemetaan, sedangkan perubahan oleh pihak lain menjadi
+            // tanggung jawab pengguna (lihat dokumentasi `MmapBackend`).
+            self.map = Some(unsafe { MmapMut::map_mut(&self.file)? });
+        }
+        Ok(())
+    }
+
+    fn bytes(&self) -> &[u8] {
+        self.map.as_deref().unwrap_or(&[])
+    }
+}
+
+impl PathBackend for MmapBackend {
+    /// Membuka sekaligus memetakan file pada path yang diberikan. File
+    /// tersebut tidak boleh dipotong maupun diubah oleh pihak lain selama
+    /// backend ini digunakan (lihat dokumentasi `MmapBackend`).
+    fn open(path: &Path) -> io::Result<MmapBackend> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+
+        MmapBackend::from_file(file)
+    }
+
+    fn create_new(path: &Path) -> io::Result<MmapBackend> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)?;
+
+        MmapBackend::from_file(file)
+    }
+}
+
+impl VolumeBackend for MmapBackend {
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+        super::read_bytes_at(self.bytes(), offset, buff)
+    }
+
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize> {
+        let range = super::write_range(offset, buff.len())?;
+
+        if range.end > self.bytes().len() {
+            self.set_len(range.end as u64)?;
+        }
+
+        if let Some(map) = self.map.as_mut() {
+            map[range].copy_from_slice(buff);
+        }
+        Ok(buff.len())
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        Ok(self.bytes().len() as u64)
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        // perubahan sebelumnya harus tersimpan terlebih dulu sebelum
+        // pemetaan yang lama dilepas
+        self.flush()?;
+        self.file.set_len(len)?;
+
+        self.remap()
+    }
+
+    // Menuliskan seluruh perubahan yang masih berada di memori ke file
+    fn flush(&mut self) -> io::Result<()> {
+        match &self.map {
+            Some(map) => map.flush(),
+            None => Ok(()),
+        }
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.file.sync_data()
+    }
+}
@@ -0,0 +1,117 @@
+use super::{ErrorKind, Result, Storage, VolumeBackend};
+
+#[derive(Debug, Eq, PartialEq)]
+enum BatchState {
+    Open,
+    Aborted,
+    Committed,
+}
+
+/// Sekumpulan operasi alloc, dealloc, dan write yang diterapkan ke
+/// volume sebagai satu kesatuan.
+///
+/// Operasi-operasi di dalam batch langsung dijalankan (sehingga alamat
+/// hasil alokasi langsung dapat digunakan oleh operasi berikutnya), namun
+/// perubahan terhadap volume baru diterapkan ketika `commit` dipanggil.
+///
+/// Jika salah satu operasi gagal, atau batch di-drop sebelum di-commit,
+/// maka seluruh operasi di dalam batch akan dibatalkan. Operasi apapun
+/// terhadap batch yang sudah dibatalkan akan menghasilkan error
+/// `ErrorKind::BatchAborted`.
+pub struct Batch<'a, B: VolumeBackend> {
+    storage: &'a mut Storage<B>,
+    state: BatchState,
+}
+
+impl<'a, B: VolumeBackend> Batch<'a, B> {
+    pub(crate) fn new(storage: &'a mut Storage<B>) -> Batch<'a, B> {
+        Batch {
+            storage,
+            state: BatchState::Open,
+        }
+    }
+
+    /// Sama seperti `Storage::alloc`, namun sebagai bagian dari batch.
+    pub fn alloc(&mut self, size: usize) -> Result<u64> {
+        self.run(|s| s.alloc_uncommitted(size))
+    }
+
+    /// Sama seperti `Storage::alloc_aligned`, namun sebagai bagian dari
+    /// batch.
+    pub fn alloc_aligned(&mut self, size: usize, align: u64) -> Result<u64> {
+        self.run(|s| s.alloc_aligned_uncommitted(size, align))
+    }
+
+    /// Sama seperti `Storage::dealloc`, namun sebagai bagian dari batch.
+    pub fn dealloc(&mut self, address: u64) -> Result<()> {
+        self.run(|s| s.dealloc_uncommitted(address))
+    }
+
+    /// Sama seperti `Storage::write`, namun sebagai bagian dari batch.
+    ///
+    /// Penulisan dapat dilakukan ke blok yang dialokasikan di dalam batch
+    /// yang sama.
+    pub fn write(&mut self, address: u64, buff: &[u8]) -> Result<usize> {
+        self.run(|s| s.write_uncommitted(address, buff))
+    }
+
+    /// Sama seperti `Storage::set_root`, namun sebagai bagian dari batch.
+    ///
+    /// Root dapat menunjuk ke blok yang dialokasikan di dalam batch yang
+    /// sama.
+    pub fn set_root(&mut self, name: &str, address: u64) -> Result<()> {
+        self.run(|s| s.set_root_uncommitted(name, address))
+    }
+
+    /// Menerapkan seluruh operasi di dalam batch ke volume.
+    ///
+    /// Jika gagal, tidak ada satupun operasi dari batch ini yang
+    /// diterapkan ke volume.
+    pub fn commit(mut self) -> Result<()> {
+        if self.state != BatchState::Open {
+            return Err(ErrorKind::BatchAborted.into());
+        }
+
+        self.state = BatchState::Committed;
+        self.storage.commit_transaction(true)
+    }
+
+    /// Membatalkan seluruh operasi di dalam batch.
+    ///
+    /// Hal yang sama juga dilakukan ketika batch di-drop tanpa di-commit,
+    /// namun method ini memberikan kesempatan untuk menangani error.
+    pub fn rollback(mut self) -> Result<()> {
+        self.abort()
+    }
+
+    fn run<T, F>(&mut self, op: F) -> Result<T>
+    where
+        F: FnOnce(&mut Storage<B>) -> Result<T>,
+    {
+        if self.state != BatchState::Open {
+            return Err(ErrorKind::BatchAborted.into());
+        }
+
+        let res = op(self.storage);
+
+        if res.is_err() {
+            self.abort()?;
+        }
+        res
+    }
+
+    fn abort(&mut self) -> Result<()> {
+        if self.state != BatchState::Open {
+            return Ok(());
+        }
+
+        self.state = BatchState::Aborted;
+        self.storage.rollback_transaction()
+    }
+}
+
+impl<'a, B: VolumeBackend> Drop for Batch<'a, B> {
+    fn drop(&mut self) {
+        let _ = self.abort();
+    }
+}
@@ -1,13 +1,257 @@
+use std::error;
+use std::fmt;
+use std::io;
+use std::mem;
+use std::path::{Path, PathBuf};
+
 #[derive(Debug)]
 pub enum ErrorKind {
     AllocatorNotInitialized,
+    BatchAborted,
     BlockNotFound,
+
+    /// Checksum dari metadata maupun isi sebuah blok tidak sesuai, yang
+    /// menandakan kerusakan data di media penyimpanan.
+    ChecksumMismatch,
+
+    /// Nama root kosong ataupun terlalu panjang.
+    InvalidRootName,
+
+    /// Alignment yang diminta bukan merupakan pangkat dua, ataupun tidak
+    /// didukung oleh allocator dari volume.
+    InvalidAlignment,
+
+    /// Argumen yang diberikan ke sebuah method tidak valid, misalnya
+    /// `GrowPolicy::Increment(0)`.
+    InvalidArgument,
+
+    /// Kegagalan pada operasi I/O terhadap media penyimpanan, beserta
+    /// error aslinya.
+    Io(io::Error),
+
+    /// Seluruh entri di tabel root sudah terisi.
+    RootTableFull,
+
     VolumeAlreadyExists,
     VolumeCorrupted,
-    VolumeInaccessible,
-    VolumeInitFailed,
     VolumeInvalidExt,
     VolumeInvalidSize,
+
+    /// Volume sedang digunakan oleh proses lain (maupun oleh `Storage`
+    /// lain di dalam proses yang sama).
+    VolumeLocked,
+
     VolumeNotEnoughSpace,
     VolumeNotFound,
+
+    /// Volume dimounting secara read-only (lihat `Storage::mount_read_only`),
+    /// sehingga tidak dapat diubah.
+    VolumeReadOnly,
+    VolumeUnknownAllocator,
+    VolumeUnsupportedFeatures,
+    VolumeUnsupportedVersion,
+}
+
+impl fmt::Display for ErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            ErrorKind::AllocatorNotInitialized => "allocator is not initialized",
+            ErrorKind::BatchAborted => "batch has been aborted",
+            ErrorKind::BlockNotFound => "block not found",
+            ErrorKind::ChecksumMismatch => "checksum mismatch",
+            ErrorKind::InvalidArgument => "invalid argument",
+            ErrorKind::InvalidAlignment => "invalid alignment",
+            ErrorKind::InvalidRootName => "invalid root name",
+            ErrorKind::RootTableFull => "root table is full",
+            ErrorKind::Io(err) => return write!(f, "i/o error: {}", err),
+            ErrorKind::VolumeAlreadyExists => "volume already exists",
+            ErrorKind::VolumeCorrupted => "volume is corrupted",
+            ErrorKind::VolumeInvalidExt => "invalid volume extension",
+            ErrorKind::VolumeInvalidSize => "invalid volume size",
+            ErrorKind::VolumeLocked => "volume is locked by another user",
+            ErrorKind::VolumeNotEnoughSpace => "not enough space in volume",
+            ErrorKind::VolumeNotFound => "volume not found",
+            ErrorKind::VolumeReadOnly => "volume is mounted read-only",
+            ErrorKind::VolumeUnknownAllocator => "unknown volume allocator",
+            ErrorKind::VolumeUnsupportedFeatures => "unsupported volume features",
+            ErrorKind::VolumeUnsupportedVersion => "unsupported volume version",
+        };
+
+        f.write_str(description)
+    }
+}
+
+// io::Error sendiri tidak dapat dibandingkan, sehingga dua error I/O
+// dianggap sama jika jenisnya (io::ErrorKind) sama. Varian lainnya tidak
+// membawa nilai apapun.
+impl PartialEq for ErrorKind {
+    fn eq(&self, other: &ErrorKind) -> bool {
+        match (self, other) {
+            (ErrorKind::Io(err), ErrorKind::Io(other)) => err.kind() == other.kind(),
+            _ => mem::discriminant(self) == mem::discriminant(other),
+        }
+    }
+}
+
+/// Error yang dihasilkan oleh package storage.
+///
+/// Selain jenis error (`ErrorKind`), error juga membawa informasi
+/// tambahan (jika tersedia) mengenai penyebabnya, seperti alamat maupun
+/// indeks dari blok, ukuran yang diharapkan beserta ukuran sebenarnya,
+/// dan path dari volume.
+///
+/// Jenis error tetap dicocokkan melalui `ErrorKind`, yaitu dengan
+/// `Error::kind`, ataupun dengan `Error::into_kind` untuk hasil dari
+/// sebuah operasi (misalnya `res.map_err(Error::into_kind)`), sehingga
+/// pattern seperti `Err(ErrorKind::BlockNotFound)` tetap dapat digunakan.
+///
+/// # Examples
+///
+/// ```
+/// use storage::{Error, ErrorKind, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+///
+/// let mut s: Storage<MemoryBackend> = Storage::default();
+/// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+///
+/// let res = s.dealloc(100);
+/// assert!(matches!(res.map_err(Error::into_kind), Err(ErrorKind::BlockNotFound)));
+///
+/// let err = s.dealloc(100).unwrap_err();
+/// assert!(matches!(err.kind(), ErrorKind::BlockNotFound));
+/// assert!(err.address() == Some(100));
+/// ```
+#[derive(Debug)]
+pub struct Error {
+    kind: ErrorKind,
+    address: Option<u64>,
+    index: Option<usize>,
+    expected: Option<u64>,
+    actual: Option<u64>,
+    path: Option<PathBuf>,
+}
+
+impl Error {
+    pub fn new(kind: ErrorKind) -> Error {
+        Error {
+            kind,
+            address: None,
+            index: None,
+            expected: None,
+            actual: None,
+            path: None,
+        }
+    }
+
+    pub(crate) fn with_address(mut self, address: u64) -> Error {
+        self.address = Some(address);
+        self
+    }
+
+    pub(crate) fn with_index(mut self, index: usize) -> Error {
+        self.index = Some(index);
+        self
+    }
+
+    pub(crate) fn with_sizes(mut self, expected: u64, actual: u64) -> Error {
+        self.expected = Some(expected);
+        self.actual = Some(actual);
+        self
+    }
+
+    pub(crate) fn with_actual(mut self, actual: u64) -> Error {
+        self.actual = Some(actual);
+        self
+    }
+
+    // Path yang sudah tercatat sebelumnya tidak akan ditimpa
+    pub(crate) fn with_path(mut self, path: &Path) -> Error {
+        if self.path.is_none() {
+            self.path = Some(path.to_path_buf());
+        }
+        self
+    }
+
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    pub fn into_kind(self) -> ErrorKind {
+        self.kind
+    }
+
+    /// Alamat (di dalam volume) yang menyebabkan error.
+    pub fn address(&self) -> Option<u64> {
+        self.address
+    }
+
+    /// Indeks dari blok yang menyebabkan error.
+    pub fn index(&self) -> Option<usize> {
+        self.index
+    }
+
+    /// Ukuran (ataupun nilai lain, seperti versi dari volume) yang
+    /// diharapkan.
+    pub fn expected(&self) -> Option<u64> {
+        self.expected
+    }
+
+    /// Ukuran (ataupun nilai lain) yang sebenarnya ditemukan.
+    pub fn actual(&self) -> Option<u64> {
+        self.actual
+    }
+
+    /// Path dari volume yang menyebabkan error.
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.kind)?;
+
+        let mut context = vec![];
+
+        if let Some(address) = self.address {
+            context.push(format!("address {}", address));
+        }
+        if let Some(index) = self.index {
+            context.push(format!("index {}", index));
+        }
+        if let Some(expected) = self.expected {
+            context.push(format!("expected {}", expected));
+        }
+        if let Some(actual) = self.actual {
+            context.push(format!("actual {}", actual));
+        }
+        if let Some(path) = &self.path {
+            context.push(format!("path {}", path.display()));
+        }
+
+        if !context.is_empty() {
+            write!(f, " ({})", context.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            ErrorKind::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Error::new(kind)
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::new(ErrorKind::Io(err))
+    }
 }
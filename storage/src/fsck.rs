@@ -0,0 +1,462 @@
+use super::alloc::rssalloc::check as rss;
+use super::alloc::slaballoc::SlabAllocator;
+use super::alloc::{AllocatorKind, Block};
+use super::mount::{self, MountValidator, VolumeLock};
+use super::wal::{Journal, Wal};
+use super::{Error, ErrorKind, FileBackend, PathBackend, Result, Superblock, VolumeBackend};
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Kerusakan yang ditemukan oleh `check` maupun `repair`.
+///
+/// Alamat dari sebuah blok merupakan alamat awal dari metadata blok
+/// tersebut di dalam volume, sedangkan index merupakan urutan blok
+/// tersebut di linked-list (dimana head berada di urutan ke-0).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Issue {
+    /// Log dari volume masih berisi transaksi yang belum diterapkan
+    /// (misalnya karena crash), sehingga hasil pemeriksaan belum tentu
+    /// sesuai dengan kondisi volume setelah dimounting.
+    UnrecoveredLog,
+
+    /// Ukuran dari head tidak sesuai, sehingga tidak ada blok yang dapat
+    /// ditelusuri.
+    BadHeadSize { expected: u64, actual: u64 },
+
+    /// Blok melewati batas volume, ataupun ukurannya tidak valid.
+    OutOfRange {
+        index: usize,
+        address: u64,
+        size: u64,
+    },
+
+    /// Blok tumpang tindih dengan blok sebelumnya, yang berakhir tepat
+    /// sebelum alamat previous_end.
+    Overlap {
+        index: usize,
+        address: u64,
+        previous_end: u64,
+    },
+
+    /// Alamat blok selanjutnya menunjuk ke blok yang sudah dilewati.
+    Cycle { index: usize, address: u64 },
+
+    /// Checksum dari metadata blok tidak sesuai.
+    ChecksumMismatch { index: usize, address: u64 },
+
+    /// Checksum dari isi blok tidak sesuai. Berbeda dengan kerusakan
+    /// lainnya, blok ini tetap diselamatkan oleh `repair`.
+    PayloadChecksumMismatch { index: usize, address: u64 },
+
+    /// Ruang yang tercatat sebagai blok terpakai di peta blok, namun tidak
+    /// dapat dijangkau dari head.
+    OrphanedSpace { address: u64, size: u64 },
+
+    /// Peta blok (pada alamat yang diberikan) tidak sesuai dengan
+    /// blok-blok yang dapat dijangkau dari head.
+    StaleBlockMap { address: u64 },
+
+    /// Halaman slab pada alamat yang diberikan bukan merupakan blok yang
+    /// valid.
+    BrokenSlabChain { address: u64 },
+
+    /// Root menunjuk ke alamat yang tidak berada di dalam blok manapun.
+    DanglingRoot { name: String, address: u64 },
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Issue::UnrecoveredLog => write!(f, "write-ahead log has not been recovered"),
+            Issue::BadHeadSize { expected, actual } => {
+                write!(
+                    f,
+                    "bad head size (expected {}, actual {})",
+                    expected, actual
+                )
+            }
+            Issue::OutOfRange {
+                index,
+                address,
+                size,
+            } => write!(
+                f,
+                "block {} at address {} is out of range (size {})",
+                index, address, size
+            ),
+            Issue::Overlap {
+                index,
+                address,
+                previous_end,
+            } => write!(
+                f,
+                "block {} at address {} overlaps previous block ending at {}",
+                index, address, previous_end
+            ),
+            Issue::Cycle { index, address } => {
+                write!(f, "block {} at address {} creates a cycle", index, address)
+            }
+            Issue::ChecksumMismatch { index, address } => write!(
+                f,
+                "metadata checksum mismatch in block {} at address {}",
+                index, address
+            ),
+            Issue::PayloadChecksumMismatch { index, address } => write!(
+                f,
+                "payload checksum mismatch in block {} at address {}",
+                index, address
+            ),
+            Issue::OrphanedSpace { address, size } => {
+                write!(f, "orphaned space at address {} (size {})", address, size)
+            }
+            Issue::StaleBlockMap { address } => {
+                write!(f, "block map at address {} is stale", address)
+            }
+            Issue::BrokenSlabChain { address } => {
+                write!(f, "slab page at address {} is not a valid block", address)
+            }
+            Issue::DanglingRoot { name, address } => {
+                write!(
+                    f,
+                    "root {:?} points to unallocated address {}",
+                    name, address
+                )
+            }
+        }
+    }
+}
+
+/// Hasil dari `check` maupun `repair`.
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct CheckReport {
+    /// Blok-blok milik pengguna yang dapat dijangkau dari head (ataupun
+    /// yang berhasil diselamatkan oleh `repair`), sebagaimana yang akan
+    /// ditampilkan oleh `Storage::blocks` (namun tanpa memecah halaman
+    /// slab menjadi objek-objek di dalamnya).
+    pub blocks: Vec<Block>,
+
+    pub issues: Vec<Issue>,
+
+    /// Volume sudah diperbaiki oleh `repair`.
+    pub is_repaired: bool,
+}
+
+impl CheckReport {
+    pub fn is_clean(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for CheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            writeln!(f, "no issues found")?;
+        } else {
+            writeln!(f, "{} issue(s) found:", self.issues.len())?;
+
+            for issue in self.issues.iter() {
+                writeln!(f, "  - {}", issue)?;
+            }
+        }
+
+        if self.is_repaired {
+            writeln!(
+                f,
+                "volume has been repaired, {} block(s) salvaged:",
+                self.blocks.len()
+            )?;
+        } else {
+            writeln!(f, "{} block(s) reachable:", self.blocks.len())?;
+        }
+
+        for block in self.blocks.iter() {
+            writeln!(f, "  - address {}, size {}", block.address, block.size)?;
+        }
+        Ok(())
+    }
+}
+
+/// Memeriksa keutuhan dari volume tanpa melakukan mounting, yaitu dengan
+/// menelusuri linked-list blok secara langsung dari volume. Volume sama
+/// sekali tidak diubah, termasuk log yang belum diterapkan (jika ada).
+///
+/// Berbeda dengan mounting, kerusakan pada blok-blok di dalam volume
+/// tidak menghasilkan error, melainkan dicatat di `CheckReport`. Error
+/// hanya dihasilkan jika superblock dari volume tidak valid, ataupun jika
+/// volume sedang dimounting oleh pihak lain (`ErrorKind::VolumeLocked`).
+///
+/// Saat ini hanya volume dengan `AllocatorKind::Rss` yang dapat diperiksa.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// let report = storage::check(Path::new("path-ke-volume.neondb")).unwrap();
+///
+/// if !report.is_clean() {
+///     println!("{}", report);
+/// }
+/// ```
+pub fn check(path: &Path) -> Result<CheckReport> {
+    MountValidator::validate(path)?;
+    let _lock = VolumeLock::acquire(path, false)?;
+
+    let mut vol = FileBackend::open(path).map_err(|err| Error::from(err).with_path(path))?;
+    let mut report = check_backend(&mut vol).map_err(|err| err.with_path(path))?;
+
+    if mount::has_unrecovered_log(path) {
+        report.issues.insert(0, Issue::UnrecoveredLog);
+    }
+
+    Ok(report)
+}
+
+/// Sama seperti `check`, namun terhadap volume yang berada di backend yang
+/// diberikan.
+pub fn check_backend<B: VolumeBackend>(vol: &mut B) -> Result<CheckReport> {
+    let superblock = MountValidator::validate_volume(vol)?;
+    let (_, report) = inspect(vol, &superblock)?;
+
+    Ok(report)
+}
+
+/// Memeriksa sekaligus memperbaiki volume, yaitu dengan memotong
+/// linked-list blok tepat sebelum blok rusak pertama. Blok-blok setelahnya
+/// (beserta isinya) tidak dapat diselamatkan, dan ruang yang ditempatinya
+/// menjadi ruang kosong.
+///
+/// Selain itu, peta blok dibuang, sedangkan root maupun halaman slab yang
+/// menunjuk ke blok yang tidak diselamatkan dilepas. Halaman slab yang
+/// dilepas tetap diselamatkan sebagai blok biasa.
+///
+/// Log dari volume (jika ada) diterapkan terlebih dulu, dan perbaikan
+/// dilakukan di dalam satu transaksi. Laporan dari perbaikan juga disimpan
+/// di samping file volume (misalnya "data.neondb-repair.txt" untuk volume
+/// "data.neondb"). Sama seperti mounting, volume dikunci secara eksklusif
+/// selama perbaikan berlangsung.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// let report = storage::repair(Path::new("path-ke-volume.neondb")).unwrap();
+///
+/// for block in report.blocks.iter() {
+///     // blok-blok yang berhasil diselamatkan
+/// }
+/// ```
+pub fn repair(path: &Path) -> Result<CheckReport> {
+    MountValidator::validate(path)?;
+    let _lock = VolumeLock::acquire(path, true)?;
+
+    let vol = FileBackend::open(path).map_err(|err| Error::from(err).with_path(path))?;
+    let log = mount::open_wal(path, false)
+        .map_err(|err| Error::from(err).with_path(&mount::wal_path(path)))?;
+
+    let mut vol = Journal::new(vol, Some(Wal::new(Box::new(log))));
+
+    let report = vol
+        .recover()
+        .and_then(|_| repair_backend(&mut vol))
+        .and_then(|report| vol.commit(true).map(|_| report))
+        .map_err(|err| err.with_path(path))?;
+
+    let report_path = mount::repair_report_path(path);
+    fs::write(&report_path, report.to_string())
+        .map_err(|err| Error::from(err).with_path(&report_path))?;
+
+    Ok(report)
+}
+
+/// Sama seperti `repair`, namun terhadap volume yang berada di backend
+/// yang diberikan (tanpa menyimpan laporan dari perbaikan). Volume yang
+/// tidak memiliki kerusakan sama sekali tidak akan diubah.
+pub fn repair_backend<B: VolumeBackend>(vol: &mut B) -> Result<CheckReport> {
+    let mut superblock = MountValidator::validate_volume(vol)?;
+    MountValidator::validate_writable(&superblock)?;
+
+    let (mut blocks, mut report) = inspect(vol, &superblock)?;
+
+    if report.is_clean() {
+        return Ok(report);
+    }
+
+    let meta_size = rss::meta_size_of(superblock.checksums);
+
+    // blok milik peta blok ikut dilepas dari linked-list
+    if let Some(map) = superblock.block_map.take() {
+        blocks.retain(|address, _| address + meta_size != map.address);
+    }
+    rss::relink_blocks(vol, &blocks, superblock.checksums)?;
+
+    report.blocks = user_blocks(&blocks, meta_size, None);
+
+    let is_broken = |issue: &Issue| matches!(issue, Issue::BrokenSlabChain { .. });
+    if report.issues.iter().any(is_broken) {
+        superblock.slab_root = None;
+    }
+
+    superblock
+        .roots
+        .retain(|_, address| find_block(&report.blocks, *address).is_some());
+
+    superblock.write_roots_to(vol)?;
+    superblock.write_to(vol)?;
+
+    report.is_repaired = true;
+    Ok(report)
+}
+
+// Menelusuri blok-blok di volume, dan mengembalikan blok-blok (termasuk
+// head) yang dapat dijangkau beserta laporannya
+fn inspect<B: VolumeBackend>(
+    vol: &mut B,
+    superblock: &Superblock,
+) -> Result<(BTreeMap<u64, u64>, CheckReport)> {
+    if superblock.allocator != AllocatorKind::Rss {
+        return Err(Error::new(ErrorKind::VolumeUnsupportedFeatures)
+            .with_actual(superblock.allocator.id() as u64));
+    }
+
+    let meta_size = rss::meta_size_of(superblock.checksums);
+    let walk = rss::walk_blocks(vol, superblock.size, superblock.checksums)?;
+    let mut issues = walk.issues;
+
+    // alamat metadata -> ukuran dari blok yang tidak dapat dijangkau
+    let mut orphans = BTreeMap::new();
+    let mut is_map_stale = false;
+
+    if let Some(map) = superblock.block_map {
+        // peta yang tidak valid akan diabaikan ketika mounting, sehingga
+        // hanya peta yang valid yang perlu dicocokkan
+        if let Some(entries) =
+            rss::read_block_map(vol, superblock.size, &map, superblock.map_generation)?
+        {
+            for (address, size) in entries.iter() {
+                if !walk.blocks.contains_key(address) {
+                    orphans.insert(*address, *size);
+                }
+            }
+
+            is_map_stale = entries != walk.blocks;
+        }
+    }
+
+    let map_address = superblock.block_map.map(|map| map.address);
+    let blocks = user_blocks(&walk.blocks, meta_size, map_address);
+    let broken_slab_page = find_broken_slab_page(vol, &blocks, superblock.slab_root)?;
+
+    // Tanpa peta blok (yang valid), blok yang tidak dapat dijangkau hanya
+    // dapat dikenali melalui alamat-alamat lain yang tercatat di volume,
+    // yaitu root, halaman slab, maupun peta blok itu sendiri.
+    let references = superblock
+        .roots
+        .values()
+        .chain(broken_slab_page.iter())
+        .chain(map_address.iter());
+
+    for address in references.filter(|address| **address >= meta_size) {
+        let meta_address = address - meta_size;
+
+        // blok tanpa isi tetap dianggap menampung alamat isinya
+        let is_reachable = walk.blocks.contains_key(&meta_address)
+            || walk
+                .blocks
+                .range(..*address)
+                .next_back()
+                .is_some_and(|(start, size)| *address < start + size);
+
+        if is_reachable {
+            continue;
+        }
+
+        let size = rss::read_block_size(vol, meta_address, superblock.size, superblock.checksums)?;
+
+        if let Some(size) = size {
+            orphans.entry(meta_address).or_insert(size);
+        }
+    }
+
+    for (address, size) in orphans {
+        issues.push(Issue::OrphanedSpace { address, size });
+    }
+
+    if let (Some(address), true) = (map_address, is_map_stale) {
+        issues.push(Issue::StaleBlockMap { address });
+    }
+
+    if let Some(address) = broken_slab_page {
+        issues.push(Issue::BrokenSlabChain { address });
+    }
+
+    for (name, address) in superblock.roots.iter() {
+        if find_block(&blocks, *address).is_none() {
+            issues.push(Issue::DanglingRoot {
+                name: name.clone(),
+                address: *address,
+            });
+        }
+    }
+
+    let report = CheckReport {
+        blocks,
+        issues,
+        is_repaired: false,
+    };
+    Ok((walk.blocks, report))
+}
+
+// Blok-blok selain head maupun peta blok, dengan alamat dan ukuran yang
+// sudah diabstraksi
+fn user_blocks(blocks: &BTreeMap<u64, u64>, meta_size: u64, map: Option<u64>) -> Vec<Block> {
+    blocks
+        .iter()
+        .skip(1)
+        .map(|(address, size)| Block {
+            address: address + meta_size,
+            size: size - meta_size,
+        })
+        .filter(|block| Some(block.address) != map)
+        .collect()
+}
+
+fn find_block(blocks: &[Block], address: u64) -> Option<&Block> {
+    blocks
+        .iter()
+        .find(|b| b.address <= address && address < b.address + b.size)
+}
+
+// Menelusuri halaman-halaman slab, dan mengembalikan alamat dari halaman
+// pertama yang bukan merupakan blok valid (jika ada)
+fn find_broken_slab_page<B: VolumeBackend>(
+    vol: &mut B,
+    blocks: &[Block],
+    root: Option<u64>,
+) -> Result<Option<u64>> {
+    let mut address = root.unwrap_or(0);
+    let mut previous = 0;
+    let mut buff = [0u8; 8];
+
+    while address != 0 {
+        let is_valid = address > previous
+            && blocks
+                .binary_search_by_key(&address, |b| b.address)
+                .is_ok_and(|i| blocks[i].size >= SlabAllocator::PAGE_SIZE);
+
+        if !is_valid {
+            return Ok(Some(address));
+        }
+
+        vol.read_exact_at(address, &mut buff)?;
+
+        previous = address;
+        address = u64::from_be_bytes(buff);
+    }
+
+    Ok(None)
+}
@@ -1,36 +1,59 @@
-use alloc::{rssalloc::RSSAllocator, Allocator, Block};
-pub use error::ErrorKind;
-use mount::MountValidator;
+use alloc::{slaballoc::SlabAllocator, Allocator};
+pub use alloc::{AllocationStats, AllocatorKind, Block};
+pub use backend::{FileBackend, MemoryBackend, MmapBackend, PathBackend, VolumeBackend};
+pub use batch::Batch;
+pub use error::{Error, ErrorKind};
+pub use fsck::{check, check_backend, repair, repair_backend, CheckReport, Issue};
+pub use mount::{has_unrecovered_log, volume_info};
+use mount::{MountValidator, VolumeLock};
 use ops::Ops;
+pub use policy::{AllocationStrategy, ChecksumMode, GrowPolicy, SyncPolicy};
+pub use superblock::Superblock;
+use wal::{Journal, Wal};
 
 use std::cmp;
-use std::fs::{File, OpenOptions};
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 pub const NEONDB_FILE_EXT: &str = "neondb";
 pub const NEONDB_FILE_MARK: &str = "A NeonDB Volume!";
-pub const NEONDB_FILE_SIZE: u64 = 1 << 23;
 
-// Setiap volume memiliki string NEONDB_FILE_MARK di beberapa byte awal,
-// dimana byte string tersebut tidak boleh diubah-ubah.
-pub const NEONDB_FILE_ALLOCATABLE_START: u64 = NEONDB_FILE_MARK.len() as u64;
-pub const NEONDB_FILE_ALLOCATABLE_SIZE: u64 = NEONDB_FILE_SIZE - NEONDB_FILE_MARK.len() as u64;
+// Ukuran volume sudah termasuk superblock, sehingga ukuran yang dapat
+// dialokasikan sedikit lebih kecil dari angka-angka di bawah ini.
+pub const NEONDB_FILE_DEFAULT_SIZE: u64 = 1 << 23;
+pub const NEONDB_FILE_MIN_SIZE: u64 = 1 << 13;
+
+// Setiap volume memiliki superblock (diawali dengan string NEONDB_FILE_MARK)
+// di beberapa byte awal, dimana byte-byte tersebut tidak boleh diubah-ubah
+// secara langsung.
+pub const NEONDB_FILE_ALLOCATABLE_START: u64 = Superblock::SIZE;
 
 mod alloc;
+mod backend;
+mod batch;
 mod error;
+mod fsck;
 mod mount;
 mod ops;
+mod policy;
+mod superblock;
+mod wal;
 
 #[cfg(test)]
 mod tests;
 
-type Result<T> = std::result::Result<T, self::error::ErrorKind>;
+type Result<T> = std::result::Result<T, self::error::Error>;
 
 /// Public API dari package storage.
 ///
 /// Package ini hanya menyediakan hal-hal terkait penyimpanan secara
 /// minimalis (low-level). Untuk implementasi detail seperti kepemilikan
 /// dari sebuah block dan sebagainya, dilakukan oleh pengguna sendiri.
+/// Meskipun begitu, alamat dari blok-blok yang menjadi titik awal data
+/// pengguna (misalnya katalog) dapat dicatat di tabel root milik volume
+/// (lihat `Storage::set_root`).
 ///
 /// # Examples
 ///
@@ -52,9 +75,32 @@ type Result<T> = std::result::Result<T, self::error::ErrorKind>;
 /// s.write(addr + 999, &bytes).unwrap(); // ilegal, error
 /// ```
 ///
-pub struct Storage {
-    volume: Option<File>,
-    allocator: Box<dyn Allocator>,
+pub struct Storage<B: VolumeBackend = FileBackend> {
+    volume: Option<Journal<B>>,
+    superblock: Option<Superblock>,
+
+    // lock terhadap file volume beserta path dari volume tersebut (untuk
+    // konteks dari error), hanya untuk volume yang dimounting melalui path
+    lock: Option<VolumeLock>,
+    path: Option<PathBuf>,
+
+    allocator: Box<dyn Allocator<Journal<B>>>,
+    slabs: SlabAllocator,
+
+    // ukuran terbesar yang dialokasikan melalui halaman slab (0 berarti
+    // tidak menggunakan halaman slab sama sekali)
+    slab_threshold: usize,
+
+    allocation_strategy: AllocationStrategy,
+
+    // cakupan checksum untuk volume yang dibuat berikutnya
+    checksum_mode: ChecksumMode,
+
+    grow_policy: GrowPolicy,
+    sync_policy: SyncPolicy,
+
+    // waktu terakhir kali volume disimpan secara permanen
+    last_sync: Instant,
 
     // cache untuk informasi dari blok yang ada di volume
     blocks_cache: Vec<Block>,
@@ -62,7 +108,7 @@ pub struct Storage {
 }
 
 impl Storage {
-    /// Membuat instance baru dari storage.
+    /// Membuat instance baru dari storage, dengan volume berupa file.
     ///
     /// Penggunaan sebelum melakukan mounting volume dari penyimpanan
     /// akan menghasilkan error.
@@ -75,17 +121,31 @@ impl Storage {
     /// let mut s = Storage::new();
     /// ```
     pub fn new() -> Storage {
-        Storage {
-            volume: None,
-            allocator: Box::new(RSSAllocator::new()),
-            blocks_cache: Vec::new(),
-            need_to_refresh_cache: true,
-        }
+        Storage::default()
     }
+}
 
+impl<B: PathBackend> Storage<B> {
     /// Melakukan mounting (atau memasang) sebuah volume yang menjadi
     /// media penyimpanan data.
     ///
+    /// Seluruh perubahan terhadap volume akan dicatat terlebih dulu di
+    /// write-ahead log (file dengan akhiran "-wal" di samping file volume).
+    /// Sisa transaksi di log tersebut, misalnya karena proses sebelumnya
+    /// mengalami crash, akan diterapkan ulang pada saat mounting.
+    ///
+    /// Backend yang digunakan untuk mengakses file volume ditentukan oleh
+    /// tipe dari storage, misalnya `Storage<MmapBackend>` untuk mengakses
+    /// volume melalui memory-mapped I/O.
+    ///
+    /// Selama dimounting, file volume dikunci (advisory lock) secara
+    /// eksklusif. Mounting terhadap volume yang sama, baik oleh proses lain
+    /// maupun oleh `Storage` lain di dalam proses yang sama, akan
+    /// menghasilkan error `ErrorKind::VolumeLocked`, begitu pula dengan
+    /// `Storage::mount_read_only`, `Storage::load`, `check`, `repair`,
+    /// maupun `volume_info`. Lock tersebut dilepas ketika volume
+    /// di-unmount, ataupun ketika `Storage` di-drop.
+    ///
     /// # Examples
     ///
     /// ```no_run
@@ -96,54 +156,403 @@ impl Storage {
     /// let vol = Path::new("path-ke-volume.neondb");
     ///
     /// s.mount(vol).unwrap();
+    ///
+    /// // mounting dengan backend yang berbeda
+    /// use storage::MmapBackend;
+    ///
+    /// let mut s: Storage<MmapBackend> = Storage::default();
+    /// s.mount(Path::new("path-ke-volume-lain.neondb")).unwrap();
     /// ```
     pub fn mount(&mut self, path: &Path) -> Result<()> {
         MountValidator::validate(path)?;
+        let lock = VolumeLock::acquire(path, true)?;
 
-        self.volume = OpenOptions::new()
-            .read(true)
-            .write(true)
-            .open(path)
-            .map_err(|_| panic!("internal error"))
-            .ok();
+        let vol = B::open(path).map_err(|err| Error::from(err).with_path(path))?;
+        let log = mount::open_wal(path, false)
+            .map_err(|err| Error::from(err).with_path(&mount::wal_path(path)))?;
 
-        self.allocator.init(self.volume.as_mut().unwrap())?;
+        self.mount_journal(Journal::new(vol, Some(Wal::new(Box::new(log)))))
+            .map_err(|err| err.with_path(path))?;
 
-        self.need_to_refresh_cache = true;
+        self.lock = Some(lock);
+        self.path = Some(path.to_path_buf());
         Ok(())
     }
 
-    /// Membuat volume baru dengan nama path yang diberikan, menginisialisasi,
-    /// sekaligus melakukan mounting terhadap volume tersebut.
+    /// Membuat volume baru dengan nama path dan ukuran (dalam byte) yang
+    /// diberikan, menginisialisasi, sekaligus melakukan mounting terhadap
+    /// volume tersebut.
+    ///
+    /// Ukuran volume sudah termasuk superblock, dan tidak boleh lebih kecil
+    /// dari `NEONDB_FILE_MIN_SIZE`.
     ///
     /// Method ini akan menghasilkan error jika volume dengan nama path yang
-    /// diberikan sudah ada sebelumnya.
+    /// diberikan sudah ada sebelumnya. Sama seperti `Storage::mount`, volume
+    /// yang dibuat dikunci secara eksklusif selama dimounting, dimana lock
+    /// tersebut sudah dipegang sejak file volume dibuat. File volume
+    /// (beserta log-nya) akan dihapus kembali jika inisialisasi gagal.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use storage::Storage;
+    /// use storage::{Storage, NEONDB_FILE_DEFAULT_SIZE};
     /// use std::path::Path;
     ///
     /// let mut s = Storage::new();
     /// let vol = Path::new("vol-yang-belum-ada.neondb");
     ///
-    /// s.mount_new(vol).unwrap();
+    /// s.mount_new(vol, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    /// ```
+    pub fn mount_new(&mut self, path: &Path, size: u64) -> Result<()> {
+        MountValidator::validate_new(path, size)?;
+        let lock = VolumeLock::create(path)?;
+
+        let res = B::open(path)
+            .map_err(|err| Error::from(err).with_path(path))
+            .and_then(|vol| {
+                let log = mount::open_wal(path, true)
+                    .map_err(|err| Error::from(err).with_path(&mount::wal_path(path)))?;
+
+                self.mount_new_journal(Journal::new(vol, Some(Wal::new(Box::new(log)))), size)
+                    .map_err(|err| err.with_path(path))
+            });
+
+        if res.is_err() {
+            drop(lock);
+
+            let _ = fs::remove_file(mount::wal_path(path));
+            let _ = fs::remove_file(path);
+        } else {
+            self.lock = Some(lock);
+            self.path = Some(path.to_path_buf());
+        }
+        res
+    }
+
+    /// Sama seperti `Storage::mount_new`, namun volume yang sudah ada pada
+    /// path yang diberikan (beserta log-nya) akan ditimpa, meskipun volume
+    /// tersebut rusak.
+    ///
+    /// Volume lama dikunci secara eksklusif hingga volume baru selesai
+    /// dibuat, sehingga volume yang sedang digunakan oleh pihak lain tidak
+    /// akan ditimpa (`ErrorKind::VolumeLocked`). Ukuran volume baru juga
+    /// divalidasi sebelum volume lama dihapus.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::{Storage, NEONDB_FILE_DEFAULT_SIZE};
+    /// use std::path::Path;
+    ///
+    /// let mut s = Storage::new();
+    /// let vol = Path::new("vol-yang-akan-ditimpa.neondb");
+    ///
+    /// s.mount_new_overwrite(vol, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    /// ```
+    pub fn mount_new_overwrite(&mut self, path: &Path, size: u64) -> Result<()> {
+        if !path.exists() {
+            return self.mount_new(path, size);
+        }
+
+        MountValidator::validate(path)?;
+        MountValidator::validate_new_size(size).map_err(|err| err.with_path(path))?;
+
+        let old_lock = VolumeLock::acquire(path, true)?;
+        fs::remove_file(path).map_err(|err| Error::from(err).with_path(path))?;
+
+        let res = self.mount_new(path, size);
+
+        drop(old_lock);
+        res
+    }
+}
+
+impl Storage<FileBackend> {
+    /// Melakukan mounting terhadap sebuah volume secara read-only.
+    ///
+    /// File volume maupun log-nya hanya dibuka untuk dibaca, dan tidak
+    /// akan diubah sama sekali. Sisa transaksi di log (jika ada) tetap
+    /// terlihat, namun hanya diterapkan di memori. Seluruh operasi yang
+    /// mengubah volume akan menghasilkan error `ErrorKind::VolumeReadOnly`.
+    ///
+    /// Selama dimounting, file volume dikunci dengan lock bersama, sehingga
+    /// volume yang sama dapat dimounting secara read-only oleh beberapa
+    /// pengguna sekaligus, namun tidak dapat dimounting melalui
+    /// `Storage::mount` (begitu pula sebaliknya).
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::Storage;
+    /// use std::path::Path;
+    ///
+    /// let mut s = Storage::new();
+    ///
+    /// s.mount_read_only(Path::new("path-ke-volume.neondb")).unwrap();
+    ///
+    /// let blocks = s.blocks().unwrap();
+    /// ```
+    pub fn mount_read_only(&mut self, path: &Path) -> Result<()> {
+        MountValidator::validate(path)?;
+        let lock = VolumeLock::acquire(path, false)?;
+
+        let vol = File::open(path)
+            .map(FileBackend::from)
+            .map_err(|err| Error::from(err).with_path(path))?;
+        let log = mount::open_wal_read_only(path)
+            .map_err(|err| Error::from(err).with_path(&mount::wal_path(path)))?;
+
+        let mut vol = Journal::new_read_only(vol);
+
+        if let Some(log) = log {
+            vol.recover_pending(&mut Wal::new(Box::new(log)))
+                .map_err(|err| err.with_path(&mount::wal_path(path)))?;
+        }
+
+        self.mount_journal(vol).map_err(|err| err.with_path(path))?;
+
+        self.lock = Some(lock);
+        self.path = Some(path.to_path_buf());
+        Ok(())
+    }
+}
+
+impl Storage<MemoryBackend> {
+    /// Membuat volume baru dengan ukuran yang diberikan, yang seluruhnya
+    /// berada di memori, sekaligus melakukan mounting terhadap volume
+    /// tersebut.
+    ///
+    /// Isi volume akan hilang begitu di-unmount, kecuali jika disimpan
+    /// terlebih dulu dengan menggunakan method `dump`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    ///
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    /// ```
+    pub fn mount_memory(&mut self, size: u64) -> Result<()> {
+        self.mount_new_backend(MemoryBackend::new(), size)
+    }
+
+    /// Menyimpan isi dari volume di memori yang sedang dimounting ke
+    /// sebuah file volume baru.
+    ///
+    /// Sama seperti `Storage::mount_new`, method ini akan menghasilkan
+    /// error jika file dengan nama path yang diberikan sudah ada.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    /// use std::path::Path;
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// // blah blah blah
+    ///
+    /// s.dump(Path::new("hasil-dump.neondb")).unwrap();
+    /// ```
+    pub fn dump(&self, path: &Path) -> Result<()> {
+        let vol = self
+            .volume
+            .as_ref()
+            .ok_or(ErrorKind::VolumeNotFound)?
+            .backend();
+
+        MountValidator::validate_new(path, vol.as_bytes().len() as u64)?;
+
+        FileBackend::create_new(path)
+            .and_then(|mut file| file.write_all_at(0, vol.as_bytes()))
+            .map_err(|err| Error::from(err).with_path(path))
+    }
+
+    /// Memuat seluruh isi dari sebuah file volume ke memori, lalu
+    /// melakukan mounting terhadap salinan tersebut.
+    ///
+    /// Perubahan yang dilakukan setelahnya tidak akan mempengaruhi file
+    /// volume aslinya. File volume hanya dikunci (dengan lock bersama)
+    /// selama isinya dibaca, sehingga error `ErrorKind::VolumeLocked` akan
+    /// dihasilkan jika volume tersebut sedang dimounting.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::{MemoryBackend, Storage};
+    /// use std::path::Path;
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    ///
+    /// s.load(Path::new("path-ke-volume.neondb")).unwrap();
+    /// ```
+    pub fn load(&mut self, path: &Path) -> Result<()> {
+        MountValidator::validate(path)?;
+        let lock = VolumeLock::acquire(path, false)?;
+
+        let bytes = fs::read(path).map_err(|err| Error::from(err).with_path(path))?;
+        drop(lock);
+
+        self.mount_backend(MemoryBackend::from_bytes(bytes))
+            .map_err(|err| err.with_path(path))
+    }
+}
+
+impl<B: VolumeBackend> Storage<B> {
+    /// Membuat instance baru dari storage, dimana volume baru yang dibuat
+    /// melalui instance tersebut akan dikelola oleh allocator dengan jenis
+    /// yang diberikan.
+    ///
+    /// Jenis allocator dicatat di superblock, sehingga volume yang sudah
+    /// ada akan selalu dimounting dengan allocator yang sesuai, terlepas
+    /// dari jenis yang diberikan di sini.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{AllocatorKind, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::with_allocator(AllocatorKind::Buddy);
+    ///
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    /// ```
+    pub fn with_allocator(kind: AllocatorKind) -> Storage<B> {
+        Storage {
+            allocator: alloc::new_allocator(kind),
+            ..Storage::default()
+        }
+    }
+
+    /// Melakukan mounting terhadap volume yang berada di backend yang
+    /// diberikan.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::{MemoryBackend, Storage};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// let backend = MemoryBackend::from_bytes(vec![]); // isi dari volume
+    ///
+    /// s.mount_backend(backend).unwrap();
+    /// ```
+    pub fn mount_backend(&mut self, vol: B) -> Result<()> {
+        self.mount_journal(Journal::new(vol, None))
+    }
+
+    /// Sama seperti `mount_backend`, namun seluruh perubahan terhadap
+    /// volume dicatat terlebih dulu di write-ahead log yang berada di
+    /// backend log.
+    ///
+    /// Sisa transaksi yang terdapat di log akan diterapkan ulang ke
+    /// volume sebelum volume divalidasi.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::{MemoryBackend, Storage};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// let backend = MemoryBackend::from_bytes(vec![]); // isi dari volume
+    /// let log = MemoryBackend::from_bytes(vec![]);     // isi dari log
+    ///
+    /// s.mount_backend_with_log(backend, Box::new(log)).unwrap();
+    /// ```
+    pub fn mount_backend_with_log(&mut self, vol: B, log: Box<dyn VolumeBackend>) -> Result<()> {
+        self.mount_journal(Journal::new(vol, Some(Wal::new(log))))
+    }
+
+    fn mount_journal(&mut self, mut vol: Journal<B>) -> Result<()> {
+        vol.recover()?;
+
+        let mut superblock = MountValidator::validate_volume(&mut vol)?;
+
+        if !vol.is_read_only() {
+            MountValidator::validate_writable(&superblock)?;
+        }
+
+        // volume selalu dikelola oleh allocator yang tercatat di superblock
+        if superblock.allocator != self.allocator.kind() {
+            self.allocator = alloc::new_allocator(superblock.allocator);
+            self.allocator.set_strategy(self.allocation_strategy);
+        }
+
+        init_allocator(self.allocator.as_mut(), &mut vol, &mut superblock)?;
+
+        self.slabs.reset();
+        init_slabs(
+            self.allocator.as_mut(),
+            &mut self.slabs,
+            &mut vol,
+            &superblock,
+        )?;
+
+        self.volume = Some(vol);
+        self.superblock = Some(superblock);
+        self.lock = None;
+        self.path = None;
+        self.last_sync = Instant::now();
+        self.need_to_refresh_cache = true;
+        Ok(())
+    }
+
+    /// Membuat volume baru di dalam backend yang diberikan (isi backend
+    /// sebelumnya akan ditimpa), sekaligus melakukan mounting terhadap
+    /// volume tersebut.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    ///
+    /// s.mount_new_backend(MemoryBackend::new(), NEONDB_FILE_DEFAULT_SIZE)
+    ///     .unwrap();
     /// ```
-    pub fn mount_new(&mut self, path: &Path) -> Result<()> {
-        MountValidator::validate_new(path)?;
+    pub fn mount_new_backend(&mut self, vol: B, size: u64) -> Result<()> {
+        self.mount_new_journal(Journal::new(vol, None), size)
+    }
+
+    fn mount_new_journal(&mut self, mut vol: Journal<B>, size: u64) -> Result<()> {
+        MountValidator::validate_new_size(size)?;
 
-        self.volume = mount::new_volume(path)
-            .map_err(|_| panic!("internal error"))
-            .ok();
-        self.allocator.init_new(self.volume.as_mut().unwrap())?;
+        self.allocator.set_checksum_mode(self.checksum_mode)?;
 
+        let superblock =
+            mount::new_volume(&mut vol, size, self.allocator.kind(), self.checksum_mode)?;
+        self.allocator.init_new(&mut vol, size)?;
+        self.slabs.reset();
+        vol.commit(true)?;
+
+        self.volume = Some(vol);
+        self.superblock = Some(superblock);
+        self.lock = None;
+        self.path = None;
+        self.last_sync = Instant::now();
         self.need_to_refresh_cache = true;
         Ok(())
     }
 
     /// Melakukan unmounting (atau melepas) volume penyimpanan yang sedang
-    /// digunakan.
+    /// digunakan, dan mengembalikan backend dari volume tersebut.
+    ///
+    /// Sebelum di-unmount, peta dari seluruh blok akan disimpan di volume
+    /// (jika masih terdapat ruang kosong), sehingga mounting berikutnya
+    /// tidak perlu memindai seluruh blok.
+    ///
+    /// Seluruh perubahan terhadap volume akan disimpan secara permanen
+    /// terlebih dulu, apapun `SyncPolicy` yang digunakan. Lock terhadap
+    /// file volume (lihat `Storage::mount`) juga dilepas. Volume yang
+    /// dimounting secara read-only tidak diubah sama sekali, termasuk peta
+    /// blok di atas.
+    ///
+    /// Hal yang sama berlaku jika sebelumnya terdapat transaksi yang gagal
+    /// diterapkan ke volume. Transaksi tersebut tetap tercatat di log, dan
+    /// akan diterapkan ulang ketika volume dimounting kembali.
     ///
     /// Error jika belum ada volume yang di-mounting.
     ///
@@ -160,15 +569,26 @@ impl Storage {
     ///
     /// s.unmount().unwrap();
     /// ```
-    pub fn unmount(&mut self) -> Result<()> {
+    pub fn unmount(&mut self) -> Result<B> {
         if self.volume.is_none() {
-            return Err(ErrorKind::VolumeNotFound);
+            return Err(ErrorKind::VolumeNotFound.into());
+        }
+
+        // volume yang tidak utuh hanya dapat dipulihkan melalui log
+        if !self.volume.as_ref().unwrap().has_unapplied() {
+            self.sync()?;
         }
-        self.volume = None;
+
+        let vol = self.volume.take().ok_or(ErrorKind::VolumeNotFound)?;
+
+        self.superblock = None;
+        self.lock = None;
+        self.path = None;
         self.allocator.reset();
+        self.slabs.reset();
         self.need_to_refresh_cache = true;
 
-        Ok(())
+        Ok(vol.into_backend())
     }
 
     /// Melakukan operasi read pada address tertentu, dan menyimpan
@@ -208,14 +628,13 @@ impl Storage {
     /// }
     /// ```
     pub fn read(&mut self, address: u64, buff: &mut [u8]) -> Result<usize> {
-        let max_len = Ops::max_operation_len_at(address, self.blocks().unwrap())?;
+        let max_len = Ops::max_operation_len_at(address, self.blocks()?)?;
         let len = cmp::min(max_len, buff.len());
 
-        Ok(Ops::read(
-            address,
-            &mut buff[..len],
-            self.volume.as_mut().unwrap(),
-        ))
+        let vol = self.volume.as_mut().unwrap();
+        self.allocator.verify_checksum(vol, address)?;
+
+        Ops::read(address, &mut buff[..len], vol)
     }
 
     /// Melakukan operasi write pada address tertentu, dengan menggunakan
@@ -255,14 +674,11 @@ impl Storage {
     /// }
     /// ```
     pub fn write(&mut self, address: u64, buff: &[u8]) -> Result<usize> {
-        let max_len = Ops::max_operation_len_at(address, self.blocks().unwrap())?;
-        let len = cmp::min(max_len, buff.len());
+        self.check_writable()?;
+
+        let res = self.write_uncommitted(address, buff);
 
-        Ok(Ops::write(
-            address,
-            &buff[..len],
-            self.volume.as_mut().unwrap(),
-        ))
+        self.finish_transaction(res)
     }
 
     /// Mengalokasikan sebuah blok dengan ukuran yang diberikan.
@@ -290,25 +706,92 @@ impl Storage {
     /// }
     /// ```
     pub fn alloc(&mut self, size: usize) -> Result<u64> {
-        if self.volume.is_none() {
-            return Err(ErrorKind::VolumeNotFound);
-        }
+        self.check_writable()?;
 
-        self.allocator
-            .alloc(self.volume.as_mut().unwrap(), size)
-            .and_then(|address| {
-                self.need_to_refresh_cache = true;
-                Ok(address)
-            })
+        let res = self.alloc_uncommitted(size);
+
+        self.finish_transaction(res)
     }
 
-    /// Men-dealokasi-kan sebuah blok yang terletak pada alamat
-    /// yang diberikan.
+    /// Sama seperti `alloc`, namun alamat yang dihasilkan selalu merupakan
+    /// kelipatan dari align (misalnya 4096 untuk halaman berukuran 4 KiB).
+    ///
+    /// Metadata dari blok tetap berada tepat sebelum alamat tersebut,
+    /// sedangkan ruang di antara blok sebelumnya dan metadata tersebut tetap
+    /// dapat digunakan oleh alokasi lain. Blok yang dihasilkan tidak pernah
+    /// ditempatkan di halaman slab.
+    ///
+    /// Error jika align bukan merupakan pangkat dua, ataupun tidak didukung
+    /// oleh allocator dari volume (misalnya `AllocatorKind::Buddy`, yang
+    /// hanya mendukung alignment hingga 16 byte).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let addr = s.alloc_aligned(4096, 4096).unwrap();
+    ///
+    /// assert!(addr.is_multiple_of(4096));
+    /// ```
+    pub fn alloc_aligned(&mut self, size: usize, align: u64) -> Result<u64> {
+        self.check_writable()?;
+
+        let res = self.alloc_aligned_uncommitted(size, align);
+
+        self.finish_transaction(res)
+    }
+
+    /// Mengubah ukuran dari blok pada alamat yang diberikan, dan
+    /// mengembalikan alamat dari blok tersebut setelah diubah ukurannya.
+    ///
+    /// Jika memungkinkan, ukuran blok akan diubah tanpa memindahkannya
+    /// (misalnya dengan menempati ruang kosong tepat setelah blok, ataupun
+    /// mengembalikan sisa blok sebagai ruang kosong). Jika tidak, blok baru
+    /// akan dialokasikan, isi dari blok lama disalin ke blok baru (sebanyak
+    /// ukuran yang lebih kecil), lalu blok lama didealokasi.
+    ///
+    /// Error jika tidak terdapat blok yang diawali oleh alamat tersebut.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let addr = s.alloc(100).unwrap();
+    /// s.write(addr, "sesuatu".as_bytes()).unwrap();
+    ///
+    /// let addr = s.realloc(addr, 200).unwrap();
+    ///
+    /// let mut buff = [0u8; 7];
+    /// s.read(addr, &mut buff).unwrap();
+    ///
+    /// assert!(&buff == b"sesuatu");
+    /// ```
+    pub fn realloc(&mut self, address: u64, new_size: usize) -> Result<u64> {
+        self.check_writable()?;
+
+        let res = self.realloc_uncommitted(address, new_size);
+
+        self.finish_transaction(res)
+    }
+
+    /// Mengatur kebijakan yang digunakan ketika volume kehabisan ruang
+    /// kosong pada saat alokasi.
+    ///
+    /// Error `ErrorKind::InvalidArgument` jika diberikan
+    /// `GrowPolicy::Increment(0)`.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use storage::Storage;
+    /// use storage::{GrowPolicy, Storage};
     /// use std::path::Path;
     ///
     /// let mut s = Storage::new();
@@ -316,53 +799,958 @@ impl Storage {
     ///
     /// s.mount(vol).unwrap();
     ///
-    /// // blah blah blah
-    ///
-    /// let res = s.dealloc(60);    // dealokasi blok pada alamat 60
-    ///
-    /// if let Err(err) = res {
-    ///     // handle error di sini
-    /// }
+    /// // perbesar volume per 1 MiB ketika ruang kosong tidak mencukupi
+    /// s.set_grow_policy(GrowPolicy::Increment(1 << 20)).unwrap();
     /// ```
-    pub fn dealloc(&mut self, address: u64) -> Result<()> {
-        if self.volume.is_none() {
-            return Err(ErrorKind::VolumeNotFound);
+    pub fn set_grow_policy(&mut self, policy: GrowPolicy) -> Result<()> {
+        if policy == GrowPolicy::Increment(0) {
+            return Err(ErrorKind::InvalidArgument.into());
         }
 
-        self.allocator
-            .dealloc(self.volume.as_mut().unwrap(), address)
-            .and_then(|_| {
-                self.need_to_refresh_cache = true;
-                Ok(())
-            })
+        self.grow_policy = policy;
+        Ok(())
     }
 
-    /// Mendapatkan informasi terkait blok-blok yang terdapat di dalam
-    /// volume yang sedang dimounting.
+    /// Mengatur kapan perubahan terhadap volume disimpan secara permanen.
+    ///
+    /// Kebijakan ini sebaiknya diatur sebelum mounting dilakukan, namun
+    /// tetap dapat diubah kapan saja.
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use storage::Storage;
+    /// use storage::{Storage, SyncPolicy};
     /// use std::path::Path;
+    /// use std::time::Duration;
     ///
     /// let mut s = Storage::new();
     /// let vol = Path::new("path-ke-volume.neondb");
     ///
+    /// // simpan secara permanen paling lama setiap 1 detik
+    /// s.set_sync_policy(SyncPolicy::Periodic(Duration::from_secs(1)));
+    ///
     /// s.mount(vol).unwrap();
+    /// ```
+    pub fn set_sync_policy(&mut self, policy: SyncPolicy) {
+        self.sync_policy = policy;
+    }
+
+    /// Mengatur strategi yang digunakan allocator dalam memilih blok
+    /// kosong untuk tiap alokasi.
+    ///
+    /// Strategi ini sebaiknya diatur sebelum mounting dilakukan, namun
+    /// tetap dapat diubah kapan saja. Strategi tidak dicatat di volume,
+    /// sehingga harus diatur ulang setiap kali storage dibuat.
+    ///
+    /// # Examples
     ///
-    /// let blocks = s.blocks().unwrap();
     /// ```
-    pub fn blocks(&mut self) -> Result<&[Block]> {
-        if self.volume.is_none() {
-            return Err(ErrorKind::VolumeNotFound);
-        }
+    /// use storage::{AllocationStrategy, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    ///
+    /// s.set_allocation_strategy(AllocationStrategy::FirstFit);
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    /// ```
+    pub fn set_allocation_strategy(&mut self, strategy: AllocationStrategy) {
+        self.allocation_strategy = strategy;
+        self.allocator.set_strategy(strategy);
+    }
 
-        if self.need_to_refresh_cache {
-            self.blocks_cache = self.allocator.blocks(self.volume.as_mut().unwrap());
-            self.need_to_refresh_cache = false;
-        }
+    /// Mengatur cakupan checksum yang disimpan di metadata tiap blok, untuk
+    /// volume yang dibuat berikutnya (`mount_new`, `mount_new_backend`,
+    /// maupun `mount_memory`). Volume yang sudah ada selalu menggunakan
+    /// cakupan checksum yang tercatat di superblock-nya.
+    ///
+    /// Checksum yang tidak sesuai akan menghasilkan error
+    /// `ErrorKind::ChecksumMismatch` (beserta alamat dari blok tersebut),
+    /// baik ketika mounting maupun ketika `read` dilakukan.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{ChecksumMode, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    ///
+    /// s.set_checksum_mode(ChecksumMode::Full);
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    /// ```
+    pub fn set_checksum_mode(&mut self, mode: ChecksumMode) {
+        self.checksum_mode = mode;
+    }
+
+    /// Mengatur ukuran terbesar (dalam byte) dari alokasi yang ditempatkan
+    /// di halaman slab, yaitu halaman yang berisi objek-objek berukuran
+    /// sama tanpa metadata milik allocator untuk tiap objeknya.
+    ///
+    /// Secara default, halaman slab tidak digunakan (threshold bernilai 0).
+    /// Volume yang memiliki halaman slab tidak dapat dimounting oleh versi
+    /// sebelumnya.
+    ///
+    /// Error `ErrorKind::InvalidArgument` jika threshold lebih besar dari
+    /// 256 byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// s.set_slab_threshold(128).unwrap();
+    ///
+    /// let a = s.alloc(24).unwrap();   // di halaman slab
+    /// let b = s.alloc(24).unwrap();   // di halaman slab yang sama
+    ///
+    /// assert!(b == a + 24);
+    /// ```
+    pub fn set_slab_threshold(&mut self, threshold: usize) -> Result<()> {
+        if threshold as u64 > SlabAllocator::MAX_OBJECT_SIZE {
+            return Err(Error::new(ErrorKind::InvalidArgument)
+                .with_sizes(SlabAllocator::MAX_OBJECT_SIZE, threshold as u64));
+        }
+
+        self.slab_threshold = threshold;
+        Ok(())
+    }
+
+    /// Menyerahkan seluruh perubahan yang masih ditahan oleh backend
+    /// (misalnya `MmapBackend`) ke sistem operasi.
+    ///
+    /// Berbeda dengan `sync`, method ini tidak menunggu perubahan tersebut
+    /// tersimpan secara permanen, sehingga perubahan masih dapat hilang
+    /// ketika sistem mengalami crash.
+    pub fn flush(&mut self) -> Result<()> {
+        self.volume
+            .as_mut()
+            .ok_or(ErrorKind::VolumeNotFound)?
+            .flush()?;
+
+        Ok(())
+    }
+
+    /// Memastikan seluruh perubahan terhadap volume (termasuk metadata
+    /// milik allocator) sudah tersimpan secara permanen.
+    ///
+    /// Peta dari seluruh blok juga ikut disimpan (lihat `Storage::unmount`),
+    /// sehingga mounting setelah crash tidak perlu memindai seluruh blok
+    /// selama susunan blok belum berubah sejak sync terakhir.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::{Storage, SyncPolicy};
+    /// use std::path::Path;
+    ///
+    /// let mut s = Storage::new();
+    /// let vol = Path::new("path-ke-volume.neondb");
+    ///
+    /// s.set_sync_policy(SyncPolicy::Never);
+    /// s.mount(vol).unwrap();
+    ///
+    /// let addr = s.alloc(100).unwrap();
+    /// s.write(addr, "sesuatu".as_bytes()).unwrap();
+    ///
+    /// s.sync().unwrap();  // alokasi maupun penulisan di atas sudah
+    ///                     // tersimpan secara permanen
+    /// ```
+    pub fn sync(&mut self) -> Result<()> {
+        let vol = self.volume.as_ref().ok_or(ErrorKind::VolumeNotFound)?;
+
+        // volume yang read-only tidak boleh diubah sama sekali
+        if !vol.is_read_only() && !vol.has_unapplied() {
+            self.save_block_map()?;
+        }
+        self.volume.as_mut().unwrap().persist()?;
+
+        self.last_sync = Instant::now();
+        Ok(())
+    }
+
+    // Operasi yang mengubah volume hanya dapat dilakukan terhadap volume
+    // yang dimounting, namun tidak secara read-only
+    fn check_writable(&self) -> Result<()> {
+        match &self.volume {
+            None => Err(ErrorKind::VolumeNotFound.into()),
+            Some(vol) if vol.is_read_only() => Err(ErrorKind::VolumeReadOnly.into()),
+            Some(_) => Ok(()),
+        }
+    }
+
+    // Seluruh penulisan yang dilakukan selama sebuah operasi hanya akan
+    // diterapkan ke volume jika operasi tersebut berhasil.
+    fn finish_transaction<T>(&mut self, res: Result<T>) -> Result<T> {
+        match res {
+            Ok(value) => self.commit_transaction(false).map(|_| value),
+            Err(err) => {
+                self.rollback_transaction()?;
+                Err(err)
+            }
+        }
+    }
+
+    // is_batch menandakan transaksi berasal dari commit sebuah batch
+    fn commit_transaction(&mut self, is_batch: bool) -> Result<()> {
+        let durable = match self.sync_policy {
+            SyncPolicy::Always => true,
+            SyncPolicy::OnCommit => is_batch,
+            SyncPolicy::Periodic(interval) => self.last_sync.elapsed() >= interval,
+            SyncPolicy::Never => false,
+        };
+
+        // perubahan (termasuk perubahan ukuran volume) baru diterapkan ke
+        // backend ketika commit, sehingga sebagian besar error dari backend
+        // baru muncul di sini
+        let path = self.path.as_deref();
+        let res = self
+            .volume
+            .as_mut()
+            .unwrap()
+            .commit(durable)
+            .map_err(|err| with_volume_path(err, path));
+
+        if res.is_ok() && durable {
+            self.last_sync = Instant::now();
+        }
+
+        // Kondisi volume tidak lagi sesuai dengan kondisi allocator,
+        // sehingga allocator perlu membaca ulang volume.
+        if res.is_err() {
+            self.reload()?;
+        }
+        res
+    }
+
+    fn rollback_transaction(&mut self) -> Result<()> {
+        if self.volume.as_mut().unwrap().rollback() {
+            self.reload()?;
+        }
+        Ok(())
+    }
+
+    // Membaca ulang superblock maupun blok-blok dari volume, sehingga
+    // perubahan yang belum diterapkan ke volume ikut terbuang.
+    fn reload(&mut self) -> Result<()> {
+        let vol = self.volume.as_mut().unwrap();
+        let mut superblock = Superblock::read_from(vol)?;
+
+        self.allocator.reset();
+        init_allocator(self.allocator.as_mut(), vol, &mut superblock)?;
+
+        self.slabs.reset();
+        init_slabs(self.allocator.as_mut(), &mut self.slabs, vol, &superblock)?;
+
+        self.superblock = Some(superblock);
+        self.need_to_refresh_cache = true;
+        Ok(())
+    }
+
+    fn write_uncommitted(&mut self, address: u64, buff: &[u8]) -> Result<usize> {
+        let max_len = Ops::max_operation_len_at(address, self.blocks()?)?;
+        let len = cmp::min(max_len, buff.len());
+
+        let vol = self.volume.as_mut().unwrap();
+        let written = Ops::write(address, &buff[..len], vol)?;
+
+        self.allocator.update_checksum(vol, address)?;
+        Ok(written)
+    }
+
+    fn alloc_uncommitted(&mut self, size: usize) -> Result<u64> {
+        self.drop_block_map()?;
+
+        let address = if self.slab_threshold > 0 && size <= self.slab_threshold {
+            let address = self.alloc_object(size)?;
+            self.update_slab_checksums()?;
+
+            address
+        } else {
+            self.alloc_block(size, 1)?
+        };
+
+        self.need_to_refresh_cache = true;
+        Ok(address)
+    }
+
+    fn alloc_aligned_uncommitted(&mut self, size: usize, align: u64) -> Result<u64> {
+        if !align.is_power_of_two() {
+            return Err(Error::new(ErrorKind::InvalidAlignment).with_actual(align));
+        }
+
+        self.drop_block_map()?;
+
+        let address = self.alloc_block(size, align)?;
+
+        self.need_to_refresh_cache = true;
+        Ok(address)
+    }
+
+    // Alokasi blok melalui allocator, dengan memperbesar volume (sesuai
+    // dengan GrowPolicy) jika diperlukan
+    fn alloc_block(&mut self, size: usize, align: u64) -> Result<u64> {
+        let mut res = self.try_alloc_block(size, align);
+
+        if let GrowPolicy::Increment(increment) = self.grow_policy {
+            // Percobaan pertama langsung memperbesar volume sebanyak
+            // ukuran yang diminta (dibulatkan ke kelipatan increment),
+            // selanjutnya cukup satu increment untuk tiap percobaan
+            // (misalnya untuk menampung metadata dari blok).
+            let mut additional = (size as u64)
+                .checked_next_multiple_of(increment)
+                .ok_or(ErrorKind::VolumeNotEnoughSpace)?;
+
+            while let Err(ErrorKind::VolumeNotEnoughSpace) = res.as_ref().map_err(Error::kind) {
+                self.grow(additional)?;
+                additional = increment;
+
+                res = self.try_alloc_block(size, align);
+            }
+        }
+
+        res
+    }
+
+    fn try_alloc_block(&mut self, size: usize, align: u64) -> Result<u64> {
+        let vol = self.volume.as_mut().unwrap();
+
+        match align {
+            1 => self.allocator.alloc(vol, size),
+            _ => self.allocator.alloc_aligned(vol, size, align),
+        }
+    }
+
+    // Alokasi objek di halaman slab, dimana halaman baru akan dialokasikan
+    // jika tidak ada halaman yang memiliki slot kosong
+    fn alloc_object(&mut self, size: usize) -> Result<u64> {
+        let size = size as u64;
+
+        if let Some(address) = self.slabs.alloc(self.volume.as_mut().unwrap(), size)? {
+            return Ok(address);
+        }
+
+        let page = self.alloc_block(SlabAllocator::PAGE_SIZE as usize, 1)?;
+        self.slabs
+            .add_page(self.volume.as_mut().unwrap(), page, size)?;
+        self.write_slab_root()?;
+
+        let address = self.slabs.alloc(self.volume.as_mut().unwrap(), size)?;
+        Ok(address.unwrap())
+    }
+
+    fn realloc_uncommitted(&mut self, address: u64, new_size: usize) -> Result<u64> {
+        self.drop_block_map()?;
+
+        let blocks = self.blocks()?;
+        let old_size = blocks
+            .binary_search_by_key(&address, |b| b.address)
+            .map(|i| blocks[i].size)
+            .map_err(|_| Error::new(ErrorKind::BlockNotFound).with_address(address))?;
+
+        // Objek di halaman slab tidak dapat diubah ukurannya, kecuali jika
+        // ukuran barunya tetap sama setelah dibulatkan
+        let is_resized = if self.slabs.owns(address) {
+            old_size == SlabAllocator::object_size_of(new_size as u64)
+        } else {
+            self.allocator
+                .resize(self.volume.as_mut().unwrap(), address, new_size)?
+        };
+
+        self.need_to_refresh_cache = true;
+
+        if is_resized {
+            return Ok(address);
+        }
+
+        let new_address = self.alloc_uncommitted(new_size)?;
+
+        let mut buff = vec![0u8; cmp::min(old_size, new_size as u64) as usize];
+        let vol = self.volume.as_mut().unwrap();
+
+        vol.read_exact_at(address, &mut buff)?;
+        vol.write_all_at(new_address, &buff)?;
+        self.allocator.update_checksum(vol, new_address)?;
+
+        self.dealloc_uncommitted(address)?;
+
+        let remap = BTreeMap::from([(address, new_address)]);
+        self.relocate_roots(&remap)?;
+
+        Ok(new_address)
+    }
+
+    fn dealloc_uncommitted(&mut self, address: u64) -> Result<()> {
+        self.drop_block_map()?;
+
+        let vol = self.volume.as_mut().unwrap();
+
+        if self.slabs.owns(address) {
+            // halaman yang sudah kosong dikembalikan ke allocator
+            if let Some(page) = self.slabs.dealloc(vol, address)? {
+                self.allocator.dealloc(vol, page)?;
+                self.write_slab_root()?;
+            }
+            self.update_slab_checksums()?;
+        } else {
+            self.allocator.dealloc(vol, address)?;
+        }
+
+        self.need_to_refresh_cache = true;
+        Ok(())
+    }
+
+    fn set_root_uncommitted(&mut self, name: &str, address: u64) -> Result<()> {
+        if name.is_empty() || name.len() > superblock::MAX_ROOT_NAME_LEN {
+            return Err(Error::new(ErrorKind::InvalidRootName)
+                .with_sizes(superblock::MAX_ROOT_NAME_LEN as u64, name.len() as u64));
+        }
+
+        let is_block = self
+            .blocks()?
+            .binary_search_by_key(&address, |b| b.address)
+            .is_ok();
+
+        if !is_block {
+            return Err(Error::new(ErrorKind::BlockNotFound).with_address(address));
+        }
+
+        let superblock = self.superblock.as_mut().unwrap();
+        let roots = &mut superblock.roots;
+
+        if roots.len() == superblock::MAX_ROOTS && !roots.contains_key(name) {
+            return Err(Error::new(ErrorKind::RootTableFull).with_index(superblock::MAX_ROOTS));
+        }
+
+        roots.insert(name.to_string(), address);
+        self.write_roots()
+    }
+
+    fn remove_root_uncommitted(&mut self, name: &str) -> Result<Option<u64>> {
+        let address = self.superblock.as_mut().unwrap().roots.remove(name);
+
+        if address.is_some() {
+            self.write_roots()?;
+        }
+        Ok(address)
+    }
+
+    // Menyesuaikan root-root yang menunjuk ke blok yang dipindahkan
+    fn relocate_roots(&mut self, remap: &BTreeMap<u64, u64>) -> Result<()> {
+        let mut is_changed = false;
+
+        for address in self.superblock.as_mut().unwrap().roots.values_mut() {
+            if let Some(new_address) = remap.get(address) {
+                *address = *new_address;
+                is_changed = true;
+            }
+        }
+
+        if is_changed {
+            self.write_roots()?;
+        }
+        Ok(())
+    }
+
+    // Tabel root beserta bit COMPAT_ROOTS di superblock
+    fn write_roots(&mut self) -> Result<()> {
+        let superblock = self.superblock.as_ref().unwrap();
+        let vol = self.volume.as_mut().unwrap();
+
+        superblock.write_roots_to(vol)?;
+        superblock.write_to(vol)
+    }
+
+    fn compact_uncommitted(&mut self) -> Result<BTreeMap<u64, u64>> {
+        self.drop_block_map()?;
+
+        let vol = self.volume.as_mut().unwrap();
+        let mut remap = self.allocator.compact(vol)?;
+
+        // halaman slab tidak terlihat oleh pengguna, sehingga yang dicatat
+        // adalah perpindahan dari objek-objek di dalamnya
+        let objects = self.slabs.relocate(vol, &remap)?;
+        if !objects.is_empty() {
+            remap.retain(|_, address| !self.slabs.owns(*address));
+            remap.extend(objects);
+
+            self.write_slab_root()?;
+            self.update_slab_checksums()?;
+        }
+
+        self.relocate_roots(&remap)?;
+
+        self.need_to_refresh_cache = true;
+        Ok(remap)
+    }
+
+    // Menghitung ulang checksum dari halaman-halaman slab yang diubah
+    fn update_slab_checksums(&mut self) -> Result<()> {
+        let vol = self.volume.as_mut().unwrap();
+
+        for page in self.slabs.take_dirty_pages() {
+            self.allocator.update_checksum(vol, page)?;
+        }
+        Ok(())
+    }
+
+    // Mencatat ulang alamat halaman slab pertama di superblock (jika
+    // berubah)
+    fn write_slab_root(&mut self) -> Result<()> {
+        let superblock = self.superblock.as_mut().unwrap();
+
+        if superblock.slab_root != self.slabs.root() {
+            superblock.slab_root = self.slabs.root();
+            superblock.write_to(self.volume.as_mut().unwrap())?;
+        }
+        Ok(())
+    }
+
+    // Peta blok tidak lagi sesuai begitu susunan blok berubah, sehingga
+    // harus dibuang (di dalam transaksi yang sama) sebelum alloc maupun
+    // dealloc dilakukan.
+    fn drop_block_map(&mut self) -> Result<()> {
+        let superblock = self.superblock.as_mut().unwrap();
+
+        if let Some(map) = superblock.block_map {
+            let vol = self.volume.as_mut().unwrap();
+            self.allocator.dealloc(vol, map.address)?;
+
+            superblock.block_map = None;
+            superblock.write_to(vol)?;
+        }
+        Ok(())
+    }
+
+    fn save_block_map(&mut self) -> Result<()> {
+        let superblock = self.superblock.as_mut().unwrap();
+
+        if superblock.block_map.is_some() {
+            return Ok(());
+        }
+
+        let vol = self.volume.as_mut().unwrap();
+
+        // generasi 0 tidak pernah digunakan, karena bagian tersebut hanya
+        // berisi byte 0 di superblock yang ditulis tanpa generasi
+        let generation = superblock.map_generation.wrapping_add(1).max(1);
+
+        let res = match self.allocator.save_map(vol, generation) {
+            Ok(Some(map)) => {
+                superblock.block_map = Some(map);
+                superblock.map_generation = generation;
+                superblock.write_to(vol)
+            }
+            Ok(None) => Ok(()),
+            Err(err) => Err(err),
+        };
+
+        match res {
+            // Volume yang sudah penuh tetap dapat di-unmount, hanya saja
+            // mounting berikutnya harus memindai seluruh blok
+            Err(err) if matches!(err.kind(), ErrorKind::VolumeNotEnoughSpace) => {
+                self.rollback_transaction()
+            }
+            res => self.finish_transaction(res),
+        }
+    }
+
+    // Memperbesar file volume sebanyak additional byte, mencatat ukuran
+    // yang baru di superblock, lalu menyerahkan ruang tambahan tersebut ke
+    // allocator sebagai ruang kosong.
+    fn grow(&mut self, additional: u64) -> Result<()> {
+        let vol = self.volume.as_mut().unwrap();
+        let superblock = self.superblock.as_mut().unwrap();
+        let new_size = superblock
+            .size
+            .checked_add(additional)
+            .ok_or(ErrorKind::VolumeNotEnoughSpace)?;
+
+        // kegagalan dari backend tetap merupakan error I/O, dengan ukuran
+        // volume sebelum dan sesudah diperbesar sebagai konteksnya
+        let path = self.path.as_deref();
+        vol.set_len(new_size).map_err(|err| {
+            with_volume_path(Error::from(err).with_sizes(superblock.size, new_size), path)
+        })?;
+
+        superblock.size = new_size;
+        superblock.write_to(vol)?;
+
+        self.allocator.extend(vol, new_size)
+    }
+
+    /// Men-dealokasi-kan sebuah blok yang terletak pada alamat
+    /// yang diberikan.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::Storage;
+    /// use std::path::Path;
+    ///
+    /// let mut s = Storage::new();
+    /// let vol = Path::new("path-ke-volume.neondb");
+    ///
+    /// s.mount(vol).unwrap();
+    ///
+    /// // blah blah blah
+    ///
+    /// let res = s.dealloc(60);    // dealokasi blok pada alamat 60
+    ///
+    /// if let Err(err) = res {
+    ///     // handle error di sini
+    /// }
+    /// ```
+    pub fn dealloc(&mut self, address: u64) -> Result<()> {
+        self.check_writable()?;
+
+        let res = self.dealloc_uncommitted(address);
+
+        self.finish_transaction(res)
+    }
+
+    /// Memindahkan blok-blok yang sedang digunakan (beserta isinya) ke
+    /// bagian awal volume, sehingga seluruh ruang kosong tergabung menjadi
+    /// satu blok kosong di bagian akhir volume.
+    ///
+    /// Nilai yang dikembalikan adalah pasangan alamat lama -> alamat baru
+    /// dari seluruh blok (maupun objek di halaman slab) yang dipindahkan,
+    /// sehingga alamat-alamat yang disimpan oleh pengguna dapat disesuaikan.
+    /// Blok yang tidak dipindahkan tidak tercatat di dalamnya.
+    ///
+    /// Blok hasil `alloc_aligned` juga dapat dipindahkan, sehingga
+    /// alamatnya belum tentu sejajar lagi setelah compaction.
+    ///
+    /// Saat ini hanya volume dengan `AllocatorKind::Rss` yang dapat
+    /// dipadatkan, sedangkan volume lain akan selalu menghasilkan peta
+    /// kosong.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let first = s.alloc(100).unwrap();
+    /// let second = s.alloc(100).unwrap();
+    ///
+    /// s.dealloc(first).unwrap();
+    ///
+    /// let remap = s.compact().unwrap();
+    ///
+    /// assert!(remap[&second] == first);
+    /// ```
+    pub fn compact(&mut self) -> Result<BTreeMap<u64, u64>> {
+        self.check_writable()?;
+
+        let res = self.compact_uncommitted();
+
+        self.finish_transaction(res)
+    }
+
+    /// Mencatat alamat dari sebuah blok di tabel root milik volume dengan
+    /// nama yang diberikan, sehingga blok tersebut dapat ditemukan kembali
+    /// (dengan `root`) setelah volume di-mounting ulang.
+    ///
+    /// Root dengan nama yang sama akan ditimpa. Alamat dari root akan ikut
+    /// disesuaikan ketika blok tersebut dipindahkan oleh `realloc` maupun
+    /// `compact`, namun tidak dihapus ketika blok tersebut didealokasi.
+    ///
+    /// Volume dapat menyimpan hingga 64 root, dengan panjang nama antara 1
+    /// hingga 23 byte. Error jika alamat yang diberikan bukan merupakan
+    /// alamat awal dari sebuah blok.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let catalog = s.alloc(100).unwrap();
+    /// s.set_root("catalog", catalog).unwrap();
+    ///
+    /// assert!(s.root("catalog").unwrap() == Some(catalog));
+    /// ```
+    pub fn set_root(&mut self, name: &str, address: u64) -> Result<()> {
+        self.check_writable()?;
+
+        let res = self.set_root_uncommitted(name, address);
+
+        self.finish_transaction(res)
+    }
+
+    /// Mendapatkan alamat blok yang tercatat di tabel root dengan nama
+    /// yang diberikan.
+    pub fn root(&self, name: &str) -> Result<Option<u64>> {
+        let superblock = self.superblock.as_ref().ok_or(ErrorKind::VolumeNotFound)?;
+
+        Ok(superblock.roots.get(name).copied())
+    }
+
+    /// Menghapus root dengan nama yang diberikan dari tabel root, dan
+    /// mengembalikan alamat blok yang sebelumnya tercatat (jika ada).
+    ///
+    /// Blok itu sendiri tidak ikut didealokasi.
+    pub fn remove_root(&mut self, name: &str) -> Result<Option<u64>> {
+        self.check_writable()?;
+
+        let res = self.remove_root_uncommitted(name);
+
+        self.finish_transaction(res)
+    }
+
+    /// Mendapatkan seluruh root yang tercatat di volume, berurutan
+    /// berdasarkan namanya.
+    pub fn roots(&self) -> Result<&BTreeMap<String, u64>> {
+        let superblock = self.superblock.as_ref().ok_or(ErrorKind::VolumeNotFound)?;
+
+        Ok(&superblock.roots)
+    }
+
+    /// Memulai sebuah batch, yaitu sekumpulan operasi alloc, dealloc,
+    /// dan write yang diterapkan ke volume sebagai satu kesatuan.
+    ///
+    /// Lihat `Batch` untuk penjelasan lebih lanjut.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::Storage;
+    /// use std::path::Path;
+    ///
+    /// let mut s = Storage::new();
+    /// let vol = Path::new("path-ke-volume.neondb");
+    ///
+    /// s.mount(vol).unwrap();
+    ///
+    /// let parent = 100;   // asumsikan blok pada alamat ini sudah
+    ///                     // dialokasikan sebelumnya
+    ///
+    /// let mut batch = s.batch().unwrap();
+    ///
+    /// let child = batch.alloc(64).unwrap();
+    /// batch.write(child, "sesuatu".as_bytes()).unwrap();
+    /// batch.write(parent, &child.to_be_bytes()).unwrap();
+    ///
+    /// batch.commit().unwrap();
+    /// ```
+    pub fn batch(&mut self) -> Result<Batch<'_, B>> {
+        self.check_writable()?;
+
+        Ok(Batch::new(self))
+    }
+
+    /// Mendapatkan statistik penggunaan ruang (termasuk fragmentasi) dari
+    /// volume yang sedang dimounting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let stats = s.stats().unwrap();
+    ///
+    /// assert!(stats.free_blocks == 1);
+    /// assert!(stats.fragmentation() == 0.0);
+    /// ```
+    pub fn stats(&self) -> Result<AllocationStats> {
+        if self.volume.is_none() {
+            return Err(ErrorKind::VolumeNotFound.into());
+        }
+
+        Ok(self.allocator.stats())
+    }
+
+    /// Mendapatkan informasi terkait blok-blok yang terdapat di dalam
+    /// volume yang sedang dimounting.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use storage::Storage;
+    /// use std::path::Path;
+    ///
+    /// let mut s = Storage::new();
+    /// let vol = Path::new("path-ke-volume.neondb");
+    ///
+    /// s.mount(vol).unwrap();
+    ///
+    /// let blocks = s.blocks().unwrap();
+    /// ```
+    pub fn blocks(&mut self) -> Result<&[Block]> {
+        if self.volume.is_none() {
+            return Err(ErrorKind::VolumeNotFound.into());
+        }
+
+        if self.need_to_refresh_cache {
+            let blocks = self.allocator.blocks(self.volume.as_mut().unwrap());
+
+            self.blocks_cache = self.slabs.blocks(blocks);
+            self.need_to_refresh_cache = false;
+        }
 
         Ok(&self.blocks_cache)
     }
+
+    /// Mendapatkan ruang-ruang kosong di dalam volume yang sedang
+    /// dimounting, terurut berdasarkan alamatnya.
+    ///
+    /// Berbeda dengan `Storage::blocks`, alamat dan ukuran dari tiap ruang
+    /// kosong merupakan nilai sebenarnya di dalam volume.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let free = s.free_blocks().unwrap();
+    ///
+    /// assert!(free.len() == 1);
+    /// ```
+    pub fn free_blocks(&self) -> Result<Vec<Block>> {
+        if self.volume.is_none() {
+            return Err(ErrorKind::VolumeNotFound.into());
+        }
+
+        Ok(self.allocator.free_blocks())
+    }
+
+    /// Mendapatkan blok-blok milik allocator (misalnya head dari linked-list
+    /// blok maupun peta blok) di dalam volume yang sedang dimounting,
+    /// terurut berdasarkan alamatnya.
+    ///
+    /// Blok-blok ini tidak ditampilkan oleh `Storage::blocks`. Sama seperti
+    /// `Storage::free_blocks`, alamat dan ukurannya merupakan nilai
+    /// sebenarnya di dalam volume.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_ALLOCATABLE_START, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// let reserved = s.reserved_blocks().unwrap();
+    ///
+    /// assert!(reserved[0].address == NEONDB_FILE_ALLOCATABLE_START);
+    /// ```
+    pub fn reserved_blocks(&self) -> Result<Vec<Block>> {
+        if self.volume.is_none() {
+            return Err(ErrorKind::VolumeNotFound.into());
+        }
+
+        Ok(self.allocator.reserved_blocks())
+    }
+
+    /// Mendapatkan superblock dari volume yang sedang dimounting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use storage::{MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+    ///
+    /// let mut s: Storage<MemoryBackend> = Storage::default();
+    /// s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    ///
+    /// assert!(s.superblock().unwrap().size == NEONDB_FILE_DEFAULT_SIZE);
+    /// ```
+    pub fn superblock(&self) -> Result<&Superblock> {
+        let superblock = self.superblock.as_ref().ok_or(ErrorKind::VolumeNotFound)?;
+
+        Ok(superblock)
+    }
+}
+
+// Menambahkan path dari volume (jika volume dimounting melalui path)
+// sebagai konteks dari error
+fn with_volume_path(err: Error, path: Option<&Path>) -> Error {
+    match path {
+        Some(path) => err.with_path(path),
+        None => err,
+    }
+}
+
+// Menginisialisasi allocator dengan peta blok yang tersimpan di volume,
+// atau dengan memindai seluruh blok jika peta tersebut tidak ada maupun
+// tidak valid.
+fn init_allocator<B: VolumeBackend>(
+    allocator: &mut dyn Allocator<Journal<B>>,
+    vol: &mut Journal<B>,
+    superblock: &mut Superblock,
+) -> Result<()> {
+    allocator.set_checksum_mode(superblock.checksums)?;
+
+    if let Some(map) = superblock.block_map {
+        if allocator
+            .load_map(vol, superblock.size, &map, superblock.map_generation)
+            .is_ok()
+        {
+            return Ok(());
+        }
+        allocator.reset();
+    }
+
+    allocator.init(vol, superblock.size)?;
+
+    // Blok milik peta yang tidak valid tetap harus dibebaskan, agar tidak
+    // terlihat sebagai blok milik pengguna.
+    if let Some(map) = superblock.block_map.take() {
+        if allocator
+            .blocks(vol)
+            .iter()
+            .any(|b| b.address == map.address)
+        {
+            allocator.dealloc(vol, map.address)?;
+        }
+
+        superblock.write_to(vol)?;
+        vol.commit(true)?;
+    }
+    Ok(())
+}
+
+// Memuat halaman-halaman slab yang tercatat di superblock. Halaman slab
+// merupakan blok biasa bagi allocator, sehingga allocator harus sudah
+// diinisialisasi terlebih dulu.
+fn init_slabs<B: VolumeBackend>(
+    allocator: &mut dyn Allocator<Journal<B>>,
+    slabs: &mut SlabAllocator,
+    vol: &mut Journal<B>,
+    superblock: &Superblock,
+) -> Result<()> {
+    let blocks = allocator.blocks(vol);
+
+    slabs.init(vol, superblock.slab_root, &blocks)
+}
+
+impl<B: VolumeBackend> Default for Storage<B> {
+    fn default() -> Self {
+        Storage {
+            volume: None,
+            superblock: None,
+            lock: None,
+            path: None,
+            allocator: alloc::new_allocator(AllocatorKind::default()),
+            slabs: SlabAllocator::new(),
+            slab_threshold: 0,
+            allocation_strategy: AllocationStrategy::default(),
+            checksum_mode: ChecksumMode::default(),
+            grow_policy: GrowPolicy::default(),
+            sync_policy: SyncPolicy::default(),
+            last_sync: Instant::now(),
+            blocks_cache: Vec::new(),
+            need_to_refresh_cache: true,
+        }
+    }
 }
@@ -1,8 +1,14 @@
-use super::{ErrorKind, Result, NEONDB_FILE_EXT, NEONDB_FILE_MARK, NEONDB_FILE_SIZE};
+use super::alloc::AllocatorKind;
+use super::superblock::SUPPORTED_RO_COMPAT_FEATURES;
+use super::{
+    ChecksumMode, Error, ErrorKind, FileBackend, PathBackend, Result, Superblock, VolumeBackend,
+    NEONDB_FILE_EXT, NEONDB_FILE_MIN_SIZE,
+};
 
-use std::fs::{File, OpenOptions};
-use std::io::{prelude::*, SeekFrom};
-use std::path::Path;
+use std::ffi::OsString;
+use std::fs::{self, File, OpenOptions, TryLockError};
+use std::io;
+use std::path::{Path, PathBuf};
 
 pub struct MountValidator;
 
@@ -11,75 +17,250 @@ impl MountValidator {
         let validator = MountValidator;
 
         if !path.exists() {
-            return Err(ErrorKind::VolumeNotFound);
+            return Err(Error::new(ErrorKind::VolumeNotFound).with_path(path));
         }
 
         validator.validate_ext(path)?;
-        validator.validate_size(path)?;
-        validator.validate_vol_mark(path)?;
 
         Ok(())
     }
 
-    pub fn validate_new(path: &Path) -> Result<()> {
+    pub fn validate_new(path: &Path, size: u64) -> Result<()> {
         let validator = MountValidator;
 
         if path.exists() {
-            return Err(ErrorKind::VolumeAlreadyExists);
+            return Err(Error::new(ErrorKind::VolumeAlreadyExists).with_path(path));
         }
 
         validator.validate_ext(path)?;
+        MountValidator::validate_new_size(size).map_err(|err| err.with_path(path))?;
 
         Ok(())
     }
 
+    // Validasi terhadap isi volume, terlepas dari backend yang digunakan
+    pub fn validate_volume<B: VolumeBackend>(vol: &mut B) -> Result<Superblock> {
+        let validator = MountValidator;
+
+        let len = validator.validate_min_size(vol)?;
+        let superblock = validator.validate_vol_mark(vol)?;
+
+        if len != superblock.size {
+            return Err(Error::new(ErrorKind::VolumeInvalidSize).with_sizes(superblock.size, len));
+        }
+
+        Ok(superblock)
+    }
+
+    // Volume dengan fitur ro-compat yang tidak dikenali hanya boleh
+    // dimounting secara read-only
+    pub fn validate_writable(superblock: &Superblock) -> Result<()> {
+        if superblock.ro_compat_features & !SUPPORTED_RO_COMPAT_FEATURES != 0 {
+            return Err(Error::new(ErrorKind::VolumeUnsupportedFeatures)
+                .with_sizes(SUPPORTED_RO_COMPAT_FEATURES, superblock.ro_compat_features));
+        }
+        Ok(())
+    }
+
+    pub fn validate_new_size(size: u64) -> Result<()> {
+        if size < NEONDB_FILE_MIN_SIZE {
+            return Err(
+                Error::new(ErrorKind::VolumeInvalidSize).with_sizes(NEONDB_FILE_MIN_SIZE, size)
+            );
+        }
+        Ok(())
+    }
+
     fn validate_ext(&self, path: &Path) -> Result<()> {
         if let Some(ext) = path.extension() {
             if ext == NEONDB_FILE_EXT {
                 return Ok(());
             }
         }
-        return Err(ErrorKind::VolumeInvalidExt);
+        Err(Error::new(ErrorKind::VolumeInvalidExt).with_path(path))
     }
 
-    fn validate_size(&self, path: &Path) -> Result<()> {
-        let metadata = match path.metadata() {
-            Ok(metadata) => metadata,
-            Err(_) => return Err(ErrorKind::VolumeInaccessible),
-        };
+    // Ukuran sebenarnya baru dapat dicocokkan setelah superblock dibaca,
+    // sehingga di sini hanya dipastikan bahwa superblock dapat dibaca.
+    fn validate_min_size<B: VolumeBackend>(&self, vol: &mut B) -> Result<u64> {
+        let len = vol.len()?;
 
-        if metadata.len() != NEONDB_FILE_SIZE {
-            return Err(ErrorKind::VolumeInvalidSize);
+        if len < NEONDB_FILE_MIN_SIZE {
+            return Err(
+                Error::new(ErrorKind::VolumeInvalidSize).with_sizes(NEONDB_FILE_MIN_SIZE, len)
+            );
         }
-        return Ok(());
+        Ok(len)
+    }
+
+    fn validate_vol_mark<B: VolumeBackend>(&self, vol: &mut B) -> Result<Superblock> {
+        // Selain NEONDB_FILE_MARK, pembacaan superblock juga memastikan
+        // bahwa versi, fitur incompat, dan allocator dari volume dikenali.
+        Superblock::read_from(vol)
+    }
+}
+
+/// Advisory lock terhadap sebuah file volume, yang dilepas ketika lock
+/// tersebut di-drop.
+pub struct VolumeLock {
+    _file: File,
+}
+
+impl VolumeLock {
+    // Lock eksklusif untuk akses read-write, sedangkan lock bersama (shared)
+    // untuk akses read-only. Lock dipegang melalui file handle tersendiri,
+    // sehingga `Storage` lain di dalam proses yang sama juga tidak dapat
+    // mendapatkan lock eksklusif terhadap volume tersebut.
+    pub fn acquire(path: &Path, is_exclusive: bool) -> Result<VolumeLock> {
+        let file = File::open(path).map_err(|err| Error::from(err).with_path(path))?;
+
+        let res = if is_exclusive {
+            file.try_lock()
+        } else {
+            file.try_lock_shared()
+        };
+
+        VolumeLock::from_result(file, res, path)
     }
 
-    fn validate_vol_mark(&self, path: &Path) -> Result<()> {
-        let mut buff = [0u8; NEONDB_FILE_MARK.len()];
+    // Membuat file volume baru, lalu langsung mengunci file tersebut secara
+    // eksklusif melalui handle yang sama, sehingga tidak ada pihak lain yang
+    // dapat membuka volume sebelum volume selesai diinisialisasi. File yang
+    // sudah terlanjur dibuat akan dihapus jika lock gagal didapatkan.
+    pub fn create(path: &Path) -> Result<VolumeLock> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::AlreadyExists => Error::new(ErrorKind::VolumeAlreadyExists),
+                _ => Error::from(err),
+            })
+            .map_err(|err| err.with_path(path))?;
 
-        let mut vol = File::open(path).unwrap();
-        vol.seek(SeekFrom::Start(0)).unwrap();
-        vol.read(&mut buff).unwrap();
+        let res = file.try_lock();
 
-        if buff != NEONDB_FILE_MARK.as_bytes() {
-            return Err(ErrorKind::VolumeCorrupted);
+        VolumeLock::from_result(file, res, path).inspect_err(|_| {
+            let _ = fs::remove_file(path);
+        })
+    }
+
+    fn from_result(
+        file: File,
+        res: std::result::Result<(), TryLockError>,
+        path: &Path,
+    ) -> Result<VolumeLock> {
+        match res {
+            Ok(()) => Ok(VolumeLock { _file: file }),
+            Err(TryLockError::WouldBlock) => {
+                Err(Error::new(ErrorKind::VolumeLocked).with_path(path))
+            }
+            Err(TryLockError::Error(err)) => Err(Error::from(err).with_path(path)),
         }
-        Ok(())
     }
 }
 
-pub fn new_volume(path: &Path) -> Result<File> {
-    let mut vol = OpenOptions::new()
+/// Membaca superblock dari volume pada path yang diberikan tanpa
+/// melakukan mounting, setelah volume tersebut divalidasi dengan cara yang
+/// sama seperti `Storage::mount`.
+///
+/// Sisa transaksi di log milik volume (jika ada) tidak diterapkan, sehingga
+/// superblock yang dihasilkan belum tentu sesuai dengan kondisi volume
+/// setelah dimounting. Error `ErrorKind::VolumeLocked` jika volume sedang
+/// dimounting oleh pihak lain.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// let superblock = storage::volume_info(Path::new("path-ke-volume.neondb")).unwrap();
+///
+/// println!("{:?}", superblock.allocator);
+/// ```
+pub fn volume_info(path: &Path) -> Result<Superblock> {
+    MountValidator::validate(path)?;
+    let _lock = VolumeLock::acquire(path, false)?;
+
+    let mut vol = FileBackend::open(path).map_err(|err| Error::from(err).with_path(path))?;
+
+    MountValidator::validate_volume(&mut vol).map_err(|err| err.with_path(path))
+}
+
+/// Memeriksa apakah log milik volume pada path yang diberikan masih berisi
+/// transaksi yang belum diterapkan ke volume (misalnya karena crash).
+///
+/// Log tersebut baru diterapkan ketika volume dimounting, sehingga
+/// `volume_info` maupun `check` belum tentu sesuai dengan kondisi volume
+/// setelah dimounting.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::path::Path;
+///
+/// if storage::has_unrecovered_log(Path::new("path-ke-volume.neondb")) {
+///     println!("volume belum dipulihkan");
+/// }
+/// ```
+pub fn has_unrecovered_log(path: &Path) -> bool {
+    wal_path(path).metadata().is_ok_and(|m| m.len() > 0)
+}
+
+pub fn new_volume<B: VolumeBackend>(
+    vol: &mut B,
+    size: u64,
+    allocator: AllocatorKind,
+    checksums: ChecksumMode,
+) -> Result<Superblock> {
+    vol.set_len(size)?;
+
+    let mut superblock = Superblock::new(size, allocator);
+    superblock.checksums = checksums;
+
+    superblock.write_to(vol)?;
+
+    Ok(superblock)
+}
+
+// Log dari WAL disimpan di file tersendiri, di samping file volume
+// (misalnya "data.neondb-wal" untuk volume "data.neondb").
+pub fn wal_path(path: &Path) -> PathBuf {
+    path_with_suffix(path, "-wal")
+}
+
+// Begitu pula dengan laporan dari `fsck::repair` (misalnya
+// "data.neondb-repair.txt" untuk volume "data.neondb").
+pub fn repair_report_path(path: &Path) -> PathBuf {
+    path_with_suffix(path, "-repair.txt")
+}
+
+fn path_with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+
+    PathBuf::from(name)
+}
+
+// Untuk volume baru, sisa log milik volume lain dengan nama yang sama
+// (jika ada) harus dibuang.
+pub fn open_wal(path: &Path, is_new_volume: bool) -> io::Result<FileBackend> {
+    OpenOptions::new()
         .read(true)
         .write(true)
-        .create_new(true)
-        .open(path)
-        .expect("creating new volume");
-
-    vol.set_len(NEONDB_FILE_SIZE)
-        .and_then(|_| vol.seek(SeekFrom::Start(0)))
-        .and_then(|_| vol.write(&NEONDB_FILE_MARK.as_bytes()))
-        .map_err(|_| ErrorKind::VolumeInitFailed)?;
+        .create(true)
+        .truncate(is_new_volume)
+        .open(wal_path(path))
+        .map(FileBackend::from)
+}
 
-    Ok(vol)
+// Membuka log hanya untuk dibaca, tanpa membuat log baru. None jika
+// volume tidak memiliki log.
+pub fn open_wal_read_only(path: &Path) -> io::Result<Option<FileBackend>> {
+    match File::open(wal_path(path)) {
+        Ok(file) => Ok(Some(FileBackend::from(file))),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
 }
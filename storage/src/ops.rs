@@ -3,8 +3,6 @@ use crate::alloc::Block;
 
 use std::cmp::Ordering;
 use std::convert::TryInto;
-use std::fs::File;
-use std::io::{prelude::*, SeekFrom};
 
 pub enum Ops {}
 
@@ -20,27 +18,23 @@ impl Ops {
                     Ordering::Less
                 }
             })
-            .map_err(|_| ErrorKind::BlockNotFound)
+            .map_err(|_| Error::new(ErrorKind::BlockNotFound).with_address(address))
     }
 
     pub fn max_operation_len_at(address: u64, blocks: &[Block]) -> Result<usize> {
-        Ops::find_block_index_of(address, blocks).and_then(|i| {
+        Ops::find_block_index_of(address, blocks).map(|i| {
             let block = &blocks[i];
             let max_len = block.size - (address - block.address);
 
-            Ok(max_len.try_into().unwrap())
+            max_len.try_into().unwrap()
         })
     }
 
-    pub fn read(address: u64, buff: &mut [u8], vol: &mut File) -> usize {
-        vol.seek(SeekFrom::Start(address))
-            .and_then(|_| vol.read(buff))
-            .expect("reading bytes from volume")
+    pub fn read<B: VolumeBackend>(address: u64, buff: &mut [u8], vol: &mut B) -> Result<usize> {
+        Ok(vol.read_at(address, buff)?)
     }
 
-    pub fn write(address: u64, buff: &[u8], vol: &mut File) -> usize {
-        vol.seek(SeekFrom::Start(address))
-            .and_then(|_| vol.write(buff))
-            .expect("writing bytes to volume")
+    pub fn write<B: VolumeBackend>(address: u64, buff: &[u8], vol: &mut B) -> Result<usize> {
+        Ok(vol.write_at(address, buff)?)
     }
 }
@@ -0,0 +1,86 @@
+use std::time::Duration;
+
+/// Kebijakan yang digunakan ketika allocator kehabisan ruang kosong.
+///
+/// Secara default, volume memiliki ukuran yang tetap (`Fixed`), sehingga
+/// alokasi yang tidak muat akan menghasilkan error
+/// `ErrorKind::VolumeNotEnoughSpace`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum GrowPolicy {
+    #[default]
+    Fixed,
+
+    /// Memperbesar file volume dengan kelipatan dari jumlah byte yang
+    /// diberikan, sampai alokasi dapat dipenuhi.
+    Increment(u64),
+}
+
+/// Kebijakan kapan perubahan terhadap volume (termasuk metadata milik
+/// allocator) disimpan secara permanen ke media penyimpanan.
+///
+/// Perubahan yang belum disimpan secara permanen dapat hilang ketika
+/// terjadi crash, namun volume tetap dapat disimpan secara permanen
+/// kapan saja dengan `Storage::sync`. Secara default, setiap operasi
+/// langsung disimpan secara permanen (`Always`).
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum SyncPolicy {
+    /// Setiap operasi (write, alloc, dealloc, maupun commit dari batch).
+    #[default]
+    Always,
+
+    /// Hanya ketika sebuah batch di-commit.
+    OnCommit,
+
+    /// Ketika sebuah operasi selesai, jika waktu yang diberikan sudah
+    /// terlewati sejak penyimpanan permanen yang terakhir.
+    Periodic(Duration),
+
+    /// Hanya ketika `Storage::sync` dipanggil (ataupun ketika volume
+    /// di-unmount).
+    Never,
+}
+
+/// Strategi yang digunakan allocator dalam memilih blok kosong untuk
+/// sebuah alokasi.
+///
+/// Saat ini hanya `RSSAllocator` yang mendukung strategi selain
+/// `BestFit`, sedangkan allocator lain akan mengabaikannya.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum AllocationStrategy {
+    /// Blok kosong terkecil yang cukup.
+    #[default]
+    BestFit,
+
+    /// Blok kosong pertama (dengan alamat terkecil) yang cukup.
+    FirstFit,
+
+    /// Sama seperti `FirstFit`, namun pencarian dimulai dari posisi
+    /// alokasi sebelumnya.
+    NextFit,
+
+    /// Blok kosong terbesar.
+    WorstFit,
+}
+
+/// Cakupan checksum (crc32c) yang disimpan di metadata tiap blok, untuk
+/// mendeteksi kerusakan data di media penyimpanan yang tidak dilaporkan
+/// sebagai error I/O.
+///
+/// Cakupan checksum dicatat di superblock ketika volume dibuat, sehingga
+/// tidak dapat diubah untuk volume yang sudah ada. Volume yang menggunakan
+/// checksum tidak dapat dimounting oleh versi sebelumnya, dan saat ini
+/// hanya didukung oleh `AllocatorKind::Rss`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum ChecksumMode {
+    #[default]
+    Disabled,
+
+    /// Hanya metadata dari tiap blok, yang diperiksa ketika mounting.
+    Metadata,
+
+    /// Metadata beserta isi dari tiap blok. Isi blok diperiksa setiap
+    /// kali `Storage::read` dilakukan, sedangkan checksum-nya dihitung
+    /// ulang setiap kali blok tersebut ditulis (sehingga penulisan ke
+    /// blok yang besar menjadi lebih lambat).
+    Full,
+}
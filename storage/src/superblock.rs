@@ -0,0 +1,317 @@
+use super::alloc::{AllocatorKind, BlockMapRef};
+use super::{ChecksumMode, Error, ErrorKind, Result, VolumeBackend, NEONDB_FILE_MARK};
+
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::io;
+
+// Versi format volume yang dipahami oleh package ini. Volume dengan
+// versi yang lebih baru tidak akan dimounting.
+pub const FORMAT_VERSION: u32 = 1;
+
+pub const DEFAULT_PAGE_SIZE: u32 = 1 << 12;
+
+// Fitur-fitur incompat yang dikenali. Berbeda dengan fitur compat yang
+// boleh diabaikan begitu saja, volume dengan fitur incompat yang tidak
+// dikenali tidak boleh dimounting.
+pub const SUPPORTED_INCOMPAT_FEATURES: u64 =
+    INCOMPAT_SLAB | INCOMPAT_META_CHECKSUMS | INCOMPAT_PAYLOAD_CHECKSUMS;
+
+// Volume memiliki halaman-halaman slab (lihat `SlabAllocator`). Versi
+// yang tidak mengenali fitur ini akan melihat tiap halaman sebagai satu
+// blok biasa, sehingga volume tidak boleh dimounting oleh versi tersebut.
+pub const INCOMPAT_SLAB: u64 = 1 << 0;
+
+// Metadata dari tiap blok memiliki checksum (lihat `ChecksumMode`), yang
+// juga mengubah ukuran metadata tersebut. Checksum dari isi blok hanya
+// dapat digunakan bersamaan dengan checksum dari metadata.
+pub const INCOMPAT_META_CHECKSUMS: u64 = 1 << 1;
+pub const INCOMPAT_PAYLOAD_CHECKSUMS: u64 = 1 << 2;
+
+// Fitur-fitur ro-compat yang dikenali. Volume dengan fitur ro-compat
+// yang tidak dikenali tetap dapat dibaca, namun hanya boleh dimounting
+// secara read-only.
+pub const SUPPORTED_RO_COMPAT_FEATURES: u64 = RO_COMPAT_BLOCK_MAP;
+
+// Volume menyimpan peta blok milik allocator (lihat `BlockMapRef`).
+// Versi yang tidak mengenali fitur ini cukup memindai seluruh blok, namun
+// tidak boleh mengubah volume, karena peta blok tidak akan ikut dibuang
+// ketika susunan blok berubah.
+pub const RO_COMPAT_BLOCK_MAP: u64 = 1 << 0;
+
+// Volume memiliki tabel root (lihat `Superblock::roots`), yang diabaikan
+// begitu saja oleh versi yang tidak mengenali fitur ini.
+pub const COMPAT_ROOTS: u64 = 1 << 1;
+
+// Tabel root berada di dalam region superblock, setelah field-field di
+// bawah ini. Tiap entri berisi 1 byte panjang nama, nama root (diisi
+// dengan byte 0), dan 8 byte alamat blok.
+pub const ROOTS_OFFSET: u64 = 1 << 10;
+pub const MAX_ROOTS: usize = 64;
+pub const MAX_ROOT_NAME_LEN: usize = 23;
+const ROOT_ENTRY_SIZE: usize = 1 + MAX_ROOT_NAME_LEN + 8;
+
+/// Superblock yang terletak di bagian paling awal dari tiap volume.
+///
+/// Superblock menempati satu region dengan ukuran tetap (`SIZE`),
+/// dimana byte-byte yang belum terpakai dicadangkan untuk field-field
+/// di versi berikutnya.
+///
+/// Layout (big-endian):
+///
+/// - 16 byte NEONDB_FILE_MARK
+/// - 4 byte versi format
+/// - 4 byte jenis allocator
+/// - 8 byte ukuran volume (termasuk superblock itu sendiri)
+/// - 16 byte UUID volume
+/// - 4 byte ukuran page
+/// - 4 byte cadangan
+/// - 8 byte fitur compat
+/// - 8 byte fitur incompat
+/// - 8 byte alamat peta blok (hanya jika fitur `RO_COMPAT_BLOCK_MAP`
+///   aktif)
+/// - 8 byte jumlah entri peta blok
+/// - 4 byte checksum (crc32c) peta blok
+/// - 4 byte generasi peta blok
+/// - 8 byte alamat halaman slab pertama (hanya jika fitur `INCOMPAT_SLAB`
+///   aktif)
+/// - 8 byte fitur ro-compat
+///
+/// Tabel root (hanya jika fitur `COMPAT_ROOTS` aktif) dimulai dari offset
+/// `ROOTS_OFFSET`, dan hanya ditulis ulang ketika isinya berubah.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Superblock {
+    pub version: u32,
+    pub allocator: AllocatorKind,
+    pub size: u64,
+    pub uuid: [u8; 16],
+    pub page_size: u32,
+    pub compat_features: u64,
+    pub incompat_features: u64,
+    pub ro_compat_features: u64,
+    pub block_map: Option<BlockMapRef>,
+    pub slab_root: Option<u64>,
+
+    // Generasi dari peta blok yang terakhir disimpan, yang juga dicatat
+    // di dalam peta tersebut. Tetap dipertahankan setelah peta dibuang,
+    // sehingga tiap peta memiliki generasi yang berbeda.
+    pub map_generation: u32,
+
+    // mengikuti bit INCOMPAT_META_CHECKSUMS dan INCOMPAT_PAYLOAD_CHECKSUMS
+    pub checksums: ChecksumMode,
+
+    // nama -> alamat blok
+    pub roots: BTreeMap<String, u64>,
+}
+
+impl Superblock {
+    pub const SIZE: u64 = 1 << 12;
+
+    // Panjang field-field yang benar-benar terpakai
+    const USED_LEN: usize = 112;
+
+    pub(crate) fn new(size: u64, allocator: AllocatorKind) -> Superblock {
+        Superblock {
+            version: FORMAT_VERSION,
+            allocator,
+            size,
+            uuid: *uuid::Uuid::new_v4().as_bytes(),
+            page_size: DEFAULT_PAGE_SIZE,
+            compat_features: 0,
+            incompat_features: 0,
+            ro_compat_features: 0,
+            block_map: None,
+            slab_root: None,
+            map_generation: 0,
+            checksums: ChecksumMode::Disabled,
+            roots: BTreeMap::new(),
+        }
+    }
+
+    pub fn read_from<B: VolumeBackend>(vol: &mut B) -> Result<Superblock> {
+        let mut buff = [0u8; Superblock::USED_LEN];
+
+        vol.read_exact_at(0, &mut buff)
+            .map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => Error::new(ErrorKind::VolumeCorrupted),
+                _ => err.into(),
+            })?;
+
+        if &buff[..16] != NEONDB_FILE_MARK.as_bytes() {
+            return Err(Error::new(ErrorKind::VolumeCorrupted).with_address(0));
+        }
+
+        let version = u32::from_be_bytes(buff[16..20].try_into().unwrap());
+        if version == 0 || version > FORMAT_VERSION {
+            return Err(Error::new(ErrorKind::VolumeUnsupportedVersion)
+                .with_sizes(FORMAT_VERSION as u64, version as u64));
+        }
+
+        let incompat_features = u64::from_be_bytes(buff[64..72].try_into().unwrap());
+        if incompat_features & !SUPPORTED_INCOMPAT_FEATURES != 0 {
+            return Err(Error::new(ErrorKind::VolumeUnsupportedFeatures)
+                .with_sizes(SUPPORTED_INCOMPAT_FEATURES, incompat_features));
+        }
+
+        let allocator_id = u32::from_be_bytes(buff[20..24].try_into().unwrap());
+        let allocator = AllocatorKind::from_id(allocator_id).ok_or_else(|| {
+            Error::new(ErrorKind::VolumeUnknownAllocator).with_actual(allocator_id as u64)
+        })?;
+
+        let page_size = u32::from_be_bytes(buff[48..52].try_into().unwrap());
+        if !page_size.is_power_of_two() {
+            return Err(Error::new(ErrorKind::VolumeCorrupted).with_address(48));
+        }
+
+        let compat_features = u64::from_be_bytes(buff[56..64].try_into().unwrap());
+        let ro_compat_features = u64::from_be_bytes(buff[104..112].try_into().unwrap());
+
+        let block_map = if ro_compat_features & RO_COMPAT_BLOCK_MAP != 0 {
+            Some(BlockMapRef {
+                address: u64::from_be_bytes(buff[72..80].try_into().unwrap()),
+                entries: u64::from_be_bytes(buff[80..88].try_into().unwrap()),
+                checksum: u32::from_be_bytes(buff[88..92].try_into().unwrap()),
+            })
+        } else {
+            None
+        };
+
+        let slab_root = if incompat_features & INCOMPAT_SLAB != 0 {
+            Some(u64::from_be_bytes(buff[96..104].try_into().unwrap()))
+        } else {
+            None
+        };
+
+        let checksums = match (
+            incompat_features & INCOMPAT_META_CHECKSUMS != 0,
+            incompat_features & INCOMPAT_PAYLOAD_CHECKSUMS != 0,
+        ) {
+            (false, false) => ChecksumMode::Disabled,
+            (true, false) => ChecksumMode::Metadata,
+            (true, true) => ChecksumMode::Full,
+            (false, true) => return Err(Error::new(ErrorKind::VolumeCorrupted).with_address(64)),
+        };
+
+        let roots = if compat_features & COMPAT_ROOTS != 0 {
+            Superblock::read_roots_from(vol)?
+        } else {
+            BTreeMap::new()
+        };
+
+        Ok(Superblock {
+            version,
+            allocator,
+            size: u64::from_be_bytes(buff[24..32].try_into().unwrap()),
+            uuid: buff[32..48].try_into().unwrap(),
+            page_size,
+            compat_features,
+            incompat_features,
+            ro_compat_features,
+            block_map,
+            slab_root,
+            map_generation: u32::from_be_bytes(buff[92..96].try_into().unwrap()),
+            checksums,
+            roots,
+        })
+    }
+
+    fn read_roots_from<B: VolumeBackend>(vol: &mut B) -> Result<BTreeMap<String, u64>> {
+        let mut buff = [0u8; MAX_ROOTS * ROOT_ENTRY_SIZE];
+        vol.read_exact_at(ROOTS_OFFSET, &mut buff)?;
+
+        let mut roots = BTreeMap::new();
+
+        for (i, entry) in buff.chunks(ROOT_ENTRY_SIZE).enumerate() {
+            let len = entry[0] as usize;
+            if len == 0 {
+                continue;
+            }
+
+            let name = entry
+                .get(1..1 + len)
+                .filter(|_| len <= MAX_ROOT_NAME_LEN)
+                .and_then(|name| String::from_utf8(name.to_vec()).ok())
+                .ok_or_else(|| {
+                    Error::new(ErrorKind::VolumeCorrupted)
+                        .with_address(ROOTS_OFFSET + (i * ROOT_ENTRY_SIZE) as u64)
+                        .with_index(i)
+                })?;
+
+            let address = &entry[1 + MAX_ROOT_NAME_LEN..];
+            roots.insert(name, u64::from_be_bytes(address.try_into().unwrap()));
+        }
+
+        Ok(roots)
+    }
+
+    pub(crate) fn write_roots_to<B: VolumeBackend>(&self, vol: &mut B) -> Result<()> {
+        debug_assert!(self.roots.len() <= MAX_ROOTS);
+
+        let mut bytes = vec![0u8; MAX_ROOTS * ROOT_ENTRY_SIZE];
+
+        for ((name, address), entry) in self.roots.iter().zip(bytes.chunks_mut(ROOT_ENTRY_SIZE)) {
+            entry[0] = name.len() as u8;
+            entry[1..1 + name.len()].copy_from_slice(name.as_bytes());
+            entry[1 + MAX_ROOT_NAME_LEN..].copy_from_slice(&address.to_be_bytes());
+        }
+
+        Ok(vol.write_all_at(ROOTS_OFFSET, &bytes)?)
+    }
+
+    pub(crate) fn write_to<B: VolumeBackend>(&self, vol: &mut B) -> Result<()> {
+        Ok(vol.write_all_at(0, &self.to_bytes())?)
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Superblock::USED_LEN);
+
+        bytes.extend_from_slice(NEONDB_FILE_MARK.as_bytes());
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        bytes.extend_from_slice(&self.allocator.id().to_be_bytes());
+        bytes.extend_from_slice(&self.size.to_be_bytes());
+        bytes.extend_from_slice(&self.uuid);
+        bytes.extend_from_slice(&self.page_size.to_be_bytes());
+        bytes.extend_from_slice(&[0u8; 4]);
+
+        // bit COMPAT_ROOTS selalu mengikuti isi tabel root
+        let compat_features = if self.roots.is_empty() {
+            self.compat_features & !COMPAT_ROOTS
+        } else {
+            self.compat_features | COMPAT_ROOTS
+        };
+        bytes.extend_from_slice(&compat_features.to_be_bytes());
+
+        // begitu pula dengan bit INCOMPAT_SLAB terhadap halaman slab
+        let mut incompat_features = match self.slab_root {
+            Some(_) => self.incompat_features | INCOMPAT_SLAB,
+            None => self.incompat_features & !INCOMPAT_SLAB,
+        };
+
+        // serta bit-bit checksum terhadap cakupan checksum
+        incompat_features &= !(INCOMPAT_META_CHECKSUMS | INCOMPAT_PAYLOAD_CHECKSUMS);
+        incompat_features |= match self.checksums {
+            ChecksumMode::Disabled => 0,
+            ChecksumMode::Metadata => INCOMPAT_META_CHECKSUMS,
+            ChecksumMode::Full => INCOMPAT_META_CHECKSUMS | INCOMPAT_PAYLOAD_CHECKSUMS,
+        };
+        bytes.extend_from_slice(&incompat_features.to_be_bytes());
+
+        let map = self.block_map.unwrap_or_default();
+        bytes.extend_from_slice(&map.address.to_be_bytes());
+        bytes.extend_from_slice(&map.entries.to_be_bytes());
+        bytes.extend_from_slice(&map.checksum.to_be_bytes());
+        bytes.extend_from_slice(&self.map_generation.to_be_bytes());
+
+        bytes.extend_from_slice(&self.slab_root.unwrap_or_default().to_be_bytes());
+
+        // begitu pula dengan bit RO_COMPAT_BLOCK_MAP terhadap peta blok
+        let ro_compat_features = match self.block_map {
+            Some(_) => self.ro_compat_features | RO_COMPAT_BLOCK_MAP,
+            None => self.ro_compat_features & !RO_COMPAT_BLOCK_MAP,
+        };
+        bytes.extend_from_slice(&ro_compat_features.to_be_bytes());
+
+        debug_assert!(bytes.len() == Superblock::USED_LEN);
+        bytes
+    }
+}
@@ -1,7 +1,22 @@
 #[macro_use]
 mod util;
 
+mod test_aligned;
 mod test_allocation;
+mod test_backend;
+mod test_batch;
+mod test_block_map;
+mod test_buddy;
+mod test_checksum;
+mod test_compact;
+mod test_error;
+mod test_fsck;
+mod test_grow;
 mod test_mounting;
 mod test_ops;
+mod test_realloc;
+mod test_roots;
+mod test_slab;
 mod test_startup;
+mod test_sync;
+mod test_wal;
@@ -0,0 +1,70 @@
+use super::*;
+use crate::{
+    AllocatorKind, Error, ErrorKind, GrowPolicy, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE,
+    NEONDB_FILE_MIN_SIZE,
+};
+
+const PAGE: u64 = 1 << 12;
+
+#[test]
+fn alloc_aligned_blocks() {
+    let mut s = util::memory_storage();
+    s.alloc(100).unwrap();
+
+    let first = s.alloc_aligned(PAGE as usize, PAGE).unwrap();
+    let second = s.alloc_aligned(PAGE as usize, PAGE).unwrap();
+
+    // metadata dari blok berikutnya tidak boleh membuang satu halaman
+    // penuh
+    assert!(
+        first.is_multiple_of(PAGE) && second.is_multiple_of(PAGE) && second == first + 2 * PAGE
+    );
+
+    // ruang sebelum blok yang sejajar tetap dapat digunakan
+    assert!({
+        let address = s.alloc(1000).unwrap();
+
+        address < first
+    });
+
+    let mut s = util::remount(s);
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 4 && blocks.iter().any(|b| b.address == first && b.size == PAGE)
+    });
+}
+
+#[test]
+fn alloc_aligned_with_grow_policy() {
+    assert!({
+        let mut s = util::memory_storage_sized(NEONDB_FILE_MIN_SIZE);
+        s.set_grow_policy(GrowPolicy::Increment(PAGE)).unwrap();
+
+        let address = s.alloc_aligned(PAGE as usize, PAGE).unwrap();
+
+        address.is_multiple_of(PAGE) && s.blocks().unwrap()[0].address == address
+    });
+}
+
+#[test]
+fn alloc_with_invalid_alignment() {
+    assert!({
+        let mut s = util::memory_storage();
+        let err = s.alloc_aligned(100, 48).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::InvalidAlignment) && err.actual() == Some(48)
+    });
+
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::with_allocator(AllocatorKind::Buddy);
+        s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+        s.alloc_aligned(100, 16).is_ok()
+            && matches!(
+                s.alloc_aligned(100, PAGE).map_err(Error::into_kind),
+                Err(ErrorKind::InvalidAlignment)
+            )
+    });
+}
@@ -1,19 +1,11 @@
 use super::*;
-use crate::Storage;
+use crate::{AllocationStrategy, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
 
-use serial_test::serial;
-
-fn init_storage() -> Storage {
-    util::fresh_volume(path_of!("tmp/storage/allocation.neondb"));
-
-    let mut s = Storage::new();
-    s.mount(path_of!("tmp/storage/allocation.neondb")).unwrap();
-
-    s
+fn init_storage() -> Storage<MemoryBackend> {
+    util::memory_storage()
 }
 
 #[test]
-#[serial]
 fn alloc_one_block() {
     assert!({
         let mut s = init_storage();
@@ -26,7 +18,6 @@ fn alloc_one_block() {
 }
 
 #[test]
-#[serial]
 fn alloc_blocks() {
     assert!({
         let mut s = init_storage();
@@ -45,7 +36,6 @@ fn alloc_blocks() {
 }
 
 #[test]
-#[serial]
 fn dealloc_one_block() {
     assert!({
         let mut s = init_storage();
@@ -75,7 +65,6 @@ fn dealloc_one_block() {
 }
 
 #[test]
-#[serial]
 fn dealloc_last_blocks() {
     assert!({
         let mut s = init_storage();
@@ -109,7 +98,6 @@ fn dealloc_last_blocks() {
 }
 
 #[test]
-#[serial]
 fn dealloc_blocks() {
     // Ketika terdapat 2 atau lebih blok kosong yang berjejeran,
     // maka blok-blok tersebut akan digabungkan menjadi satu.
@@ -149,3 +137,115 @@ fn dealloc_blocks() {
         s.blocks().unwrap()[1].address == address
     });
 }
+
+#[test]
+fn alloc_best_fit() {
+    // Blok kosong terkecil yang cukup untuk menampung data akan
+    // dipilih, meskipun terdapat blok kosong lain yang lebih besar
+    // di alamat yang lebih awal.
+    assert!({
+        let mut s = init_storage();
+        let mut addresses = vec![];
+
+        for size in [256, 64, 64, 64].iter() {
+            addresses.push(s.alloc(*size).unwrap());
+        }
+
+        s.dealloc(addresses[0]).unwrap();
+        s.dealloc(addresses[2]).unwrap();
+
+        s.alloc(64).unwrap() == addresses[2] && s.alloc(64).unwrap() == addresses[0]
+    });
+}
+
+// Menghasilkan storage dengan 3 blok kosong: 272 byte, 80 byte, dan sisa
+// volume (secara berurutan), beserta alamat dari tiap blok
+fn fragmented_storage(strategy: AllocationStrategy) -> (Storage<MemoryBackend>, Vec<u64>) {
+    let mut s = init_storage();
+    s.set_allocation_strategy(strategy);
+
+    let addresses = [64, 256, 64, 64, 64]
+        .iter()
+        .map(|size| s.alloc(*size).unwrap())
+        .collect::<Vec<u64>>();
+
+    s.dealloc(addresses[1]).unwrap();
+    s.dealloc(addresses[3]).unwrap();
+
+    (s, addresses)
+}
+
+#[test]
+fn alloc_with_strategies() {
+    let tail = |addresses: &[u64]| addresses[4] + 64 + 16;
+
+    assert!({
+        let (mut s, addresses) = fragmented_storage(AllocationStrategy::BestFit);
+
+        s.alloc(64).unwrap() == addresses[3]
+    });
+
+    assert!({
+        let (mut s, addresses) = fragmented_storage(AllocationStrategy::FirstFit);
+
+        s.alloc(64).unwrap() == addresses[1]
+    });
+
+    assert!({
+        let (mut s, addresses) = fragmented_storage(AllocationStrategy::WorstFit);
+
+        s.alloc(64).unwrap() == tail(&addresses)
+    });
+
+    // pencarian dilanjutkan dari posisi alokasi sebelumnya, lalu kembali
+    // ke awal volume jika tidak ditemukan
+    assert!({
+        let (mut s, addresses) = fragmented_storage(AllocationStrategy::NextFit);
+
+        let first = s.alloc(64).unwrap();
+
+        // penuhi sisa volume
+        let remaining = s.stats().unwrap().largest_free_block;
+        s.alloc((remaining - 16) as usize).unwrap();
+
+        first == tail(&addresses) && s.alloc(64).unwrap() == addresses[1]
+    });
+}
+
+#[test]
+fn allocation_stats() {
+    assert!({
+        let s = init_storage();
+        let stats = s.stats().unwrap();
+
+        // hanya terdapat head
+        stats.used_blocks == 1 && stats.free_blocks == 1 && stats.fragmentation() == 0.0
+    });
+
+    assert!({
+        let (s, _) = fragmented_storage(AllocationStrategy::BestFit);
+        let stats = s.stats().unwrap();
+
+        stats.used_blocks == 4
+            && stats.free_blocks == 3
+            && stats.free_bytes - stats.largest_free_block == 272 + 80
+            && stats.used_bytes + stats.free_bytes == NEONDB_FILE_DEFAULT_SIZE - 4096
+            && stats.fragmentation() > 0.0
+    });
+}
+
+#[test]
+fn free_blocks_after_dealloc() {
+    assert!({
+        let mut s = init_storage();
+
+        let first = s.alloc(100).unwrap();
+        s.alloc(100).unwrap();
+        s.dealloc(first).unwrap();
+
+        // ruang kosong dihitung beserta metadata milik blok yang dihapus
+        let free = s.free_blocks().unwrap();
+
+        free.len() == 2 && free[0].address == first - 16 && free[0].size == 116
+    });
+}
@@ -0,0 +1,171 @@
+use super::*;
+use crate::{
+    Error, ErrorKind, MemoryBackend, MmapBackend, Storage, VolumeBackend, NEONDB_FILE_DEFAULT_SIZE,
+};
+
+use serial_test::serial;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+#[test]
+fn memory_backend_read_write() {
+    let mut vol = MemoryBackend::new();
+
+    assert!({
+        let n = vol.write_at(8, &[1u8; 8]).unwrap();
+
+        // penulisan melewati ukuran backend memperbesar backend
+        n == 8 && vol.len().unwrap() == 16
+    });
+
+    assert!({
+        let mut buff = [9u8; 12];
+        let n = vol.read_at(4, &mut buff).unwrap();
+
+        n == 12 && buff[..4] == [0u8; 4] && buff[4..] == [1u8; 8]
+    });
+
+    // pembacaan melewati ukuran backend terpotong
+    assert!({
+        let mut buff = [0u8; 16];
+
+        vol.read_at(12, &mut buff).unwrap() == 4 && vol.read_at(64, &mut buff).unwrap() == 0
+    });
+}
+
+#[test]
+fn mount_memory_backend() {
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_new_backend(MemoryBackend::new(), NEONDB_FILE_DEFAULT_SIZE)
+        .unwrap();
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"in memory").unwrap();
+
+    let vol = s.unmount().unwrap();
+
+    assert!(vol.len().unwrap() == NEONDB_FILE_DEFAULT_SIZE);
+
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::default();
+        s.mount_backend(vol).unwrap();
+
+        let mut buff = [0u8; 9];
+        s.read(address, &mut buff).unwrap();
+
+        &buff == b"in memory"
+    });
+}
+
+#[test]
+#[serial]
+fn dump_and_load_memory_volume() {
+    let p = path_of!("tmp/storage/dump.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = util::memory_storage();
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"dumped").unwrap();
+
+    s.dump(p).unwrap();
+
+    // hasil dump merupakan volume yang valid
+    assert!({
+        let mut s = Storage::new();
+        s.mount(p).unwrap();
+
+        s.blocks().unwrap()[0].address == address
+    });
+
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::default();
+        s.load(p).unwrap();
+
+        let mut buff = [0u8; 6];
+        s.read(address, &mut buff).unwrap();
+
+        &buff == b"dumped"
+    });
+}
+
+#[test]
+#[serial]
+fn dump_to_existing_volume() {
+    assert!({
+        let p = path_of!("tmp/storage/dump_existing.neondb");
+        util::fresh_volume(p);
+
+        let s = util::memory_storage();
+        let res = s.dump(p);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeAlreadyExists)
+        )
+    });
+}
+
+#[test]
+#[serial]
+fn mount_mmap_backend() {
+    let p = path_of!("tmp/storage/mmap.neondb");
+    util::ensure_not_exists(p);
+
+    let address = {
+        let mut s: Storage<MmapBackend> = Storage::default();
+        s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+        let address = s.alloc(64).unwrap();
+        s.write(address, b"mapped").unwrap();
+
+        s.unmount().unwrap().flush().unwrap();
+        address
+    };
+
+    // volume yang sama dapat dibaca melalui backend lain
+    assert!({
+        let mut s = Storage::new();
+        s.mount(p).unwrap();
+
+        let mut buff = [0u8; 6];
+        s.read(address, &mut buff).unwrap();
+
+        s.blocks().unwrap().len() == 1 && &buff == b"mapped"
+    });
+}
+
+#[test]
+fn io_error_from_backend() {
+    let fail_writes = Rc::new(Cell::new(false));
+    let vol = util::FaultyBackend {
+        inner: MemoryBackend::new(),
+        fail_writes: Rc::clone(&fail_writes),
+    };
+
+    let mut s = Storage::default();
+    s.mount_new_backend(vol, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    let address = s.alloc(64).unwrap();
+
+    fail_writes.set(true);
+
+    assert!(matches!(
+        s.write(address, b"lost").map_err(Error::into_kind),
+        Err(ErrorKind::Io(_))
+    ));
+    assert!(matches!(
+        s.alloc(64).map_err(Error::into_kind),
+        Err(ErrorKind::Io(_))
+    ));
+
+    // storage tetap dapat digunakan setelah backend kembali normal
+    fail_writes.set(false);
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 1 && blocks[0].address == address
+    });
+    assert!(s.write(address, b"kept").is_ok());
+}
@@ -0,0 +1,109 @@
+use super::*;
+use crate::alloc::Block;
+use crate::{Error, ErrorKind, MemoryBackend, Storage};
+
+fn read_bytes(s: &mut Storage<MemoryBackend>, address: u64, len: usize) -> Vec<u8> {
+    let mut buff = vec![0u8; len];
+    s.read(address, &mut buff).unwrap();
+
+    buff
+}
+
+#[test]
+fn commit_batch() {
+    let mut s = util::memory_storage();
+    let parent = s.alloc(8).unwrap();
+
+    let child = {
+        let mut batch = s.batch().unwrap();
+
+        let child = batch.alloc(64).unwrap();
+        batch.write(child, b"child").unwrap();
+        batch.write(parent, &child.to_be_bytes()).unwrap();
+
+        batch.commit().unwrap();
+        child
+    };
+
+    let mut s = util::remount(s);
+
+    assert!(s.blocks().unwrap().len() == 2);
+    assert!(read_bytes(&mut s, parent, 8) == child.to_be_bytes());
+    assert!(read_bytes(&mut s, child, 5) == b"child");
+}
+
+#[test]
+fn drop_batch_without_commit() {
+    let mut s = util::memory_storage();
+    let address = s.alloc(64).unwrap();
+
+    {
+        let mut batch = s.batch().unwrap();
+
+        batch.alloc(64).unwrap();
+        batch.dealloc(address).unwrap();
+    }
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 1 && blocks[0].address == address
+    });
+
+    // operasi biasa setelah batch dibatalkan tetap dapat dilakukan
+    assert!(s.alloc(64).is_ok());
+}
+
+#[test]
+fn failed_operation_aborts_batch() {
+    let mut s = util::memory_storage();
+    let address = s.alloc(64).unwrap();
+
+    let mut batch = s.batch().unwrap();
+
+    batch.write(address, b"discarded").unwrap();
+    batch.alloc(64).unwrap();
+
+    assert!(matches!(
+        batch.dealloc(address + 8).map_err(Error::into_kind),
+        Err(ErrorKind::BlockNotFound)
+    ));
+    assert!(matches!(
+        batch.alloc(64).map_err(Error::into_kind),
+        Err(ErrorKind::BatchAborted)
+    ));
+    assert!(matches!(
+        batch.commit().map_err(Error::into_kind),
+        Err(ErrorKind::BatchAborted)
+    ));
+
+    let mut s = util::remount(s);
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 1 && blocks[0] == Block { address, size: 64 }
+    });
+    assert!(read_bytes(&mut s, address, 9) == [0u8; 9]);
+}
+
+#[test]
+fn explicit_rollback() {
+    let mut s = util::memory_storage();
+
+    let mut batch = s.batch().unwrap();
+    batch.alloc(64).unwrap();
+    batch.rollback().unwrap();
+
+    assert!(s.blocks().unwrap().is_empty());
+}
+
+#[test]
+fn batch_without_volume() {
+    let mut s: Storage<MemoryBackend> = Storage::default();
+
+    assert!(matches!(
+        s.batch().map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotFound)
+    ));
+}
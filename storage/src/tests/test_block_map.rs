@@ -0,0 +1,201 @@
+use super::*;
+use crate::superblock::Superblock;
+use crate::{
+    alloc::Block, FileBackend, MemoryBackend, PathBackend, Storage, VolumeBackend,
+    NEONDB_FILE_ALLOCATABLE_START, NEONDB_FILE_DEFAULT_SIZE, NEONDB_FILE_MIN_SIZE,
+};
+
+use serial_test::serial;
+
+// Volume berisi beberapa blok (salah satunya sudah didealokasi), beserta
+// daftar blok-blok tersebut
+fn volume_with_blocks() -> (MemoryBackend, Vec<Block>) {
+    let mut s = util::memory_storage();
+
+    let addresses = (0..5).map(|_| s.alloc(64).unwrap()).collect::<Vec<u64>>();
+    s.dealloc(addresses[2]).unwrap();
+
+    let blocks = s
+        .blocks()
+        .unwrap()
+        .iter()
+        .map(|b| Block { ..*b })
+        .collect::<Vec<Block>>();
+
+    (s.unmount().unwrap(), blocks)
+}
+
+fn mount(vol: MemoryBackend) -> Storage<MemoryBackend> {
+    let mut s = Storage::default();
+    s.mount_backend(vol).unwrap();
+
+    s
+}
+
+#[test]
+fn block_map_saved_on_unmount() {
+    let (mut vol, blocks) = volume_with_blocks();
+
+    assert!(Superblock::read_from(&mut vol).unwrap().block_map.is_some());
+
+    // blok yang berisi peta tidak terlihat oleh pengguna
+    assert!(mount(vol).blocks().unwrap() == blocks.as_slice());
+}
+
+#[test]
+fn mount_without_scanning_blocks() {
+    let (mut vol, blocks) = volume_with_blocks();
+
+    // rantai blok di volume dirusak, sehingga pemindaian blok akan gagal
+    vol.write_all_at(NEONDB_FILE_ALLOCATABLE_START + 8, &1u64.to_be_bytes())
+        .unwrap();
+
+    assert!(mount(vol).blocks().unwrap() == blocks.as_slice());
+}
+
+#[test]
+fn mount_with_zero_size_block() {
+    let mut s = util::memory_storage();
+
+    s.alloc(64).unwrap();
+    s.alloc(0).unwrap();
+    s.alloc(64).unwrap();
+
+    let blocks = s
+        .blocks()
+        .unwrap()
+        .iter()
+        .map(|b| Block { ..*b })
+        .collect::<Vec<Block>>();
+
+    let mut vol = s.unmount().unwrap();
+
+    // peta blok tetap digunakan, sehingga rantai blok yang rusak tidak
+    // pernah dipindai
+    vol.write_all_at(NEONDB_FILE_ALLOCATABLE_START + 8, &1u64.to_be_bytes())
+        .unwrap();
+
+    assert!(mount(vol).blocks().unwrap() == blocks.as_slice());
+}
+
+#[test]
+fn fallback_to_scan_on_invalid_block_map() {
+    let (mut vol, blocks) = volume_with_blocks();
+
+    let map = Superblock::read_from(&mut vol).unwrap().block_map.unwrap();
+    vol.write_all_at(map.address, &[0xff; 4]).unwrap();
+
+    let mut s = mount(vol);
+
+    assert!(s.blocks().unwrap() == blocks.as_slice());
+
+    // blok yang berisi peta yang rusak langsung dibebaskan
+    assert!({
+        let mut vol = s.unmount().unwrap();
+        let new_map = Superblock::read_from(&mut vol).unwrap().block_map.unwrap();
+
+        new_map.address == map.address && mount(vol).blocks().unwrap() == blocks.as_slice()
+    });
+}
+
+#[test]
+fn reject_block_map_from_other_generation() {
+    let (mut vol, mut blocks) = volume_with_blocks();
+
+    // superblock ditulis ulang oleh versi yang tidak mengenali generasi
+    // peta, yang kemudian juga melepas blok terakhir dari rantai blok
+    let mut superblock = Superblock::read_from(&mut vol).unwrap();
+    superblock.map_generation = 0;
+    superblock.write_to(&mut vol).unwrap();
+
+    blocks.pop();
+    let meta_address = blocks.last().unwrap().address - 16;
+    vol.write_all_at(meta_address + 8, &0u64.to_be_bytes())
+        .unwrap();
+
+    assert!(mount(vol).blocks().unwrap() == blocks.as_slice());
+}
+
+#[test]
+#[serial]
+fn block_map_saved_on_sync() {
+    let p = path_of!("tmp/storage/block_map_sync.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = Storage::new();
+    s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    s.alloc(64).unwrap();
+    s.sync().unwrap();
+
+    let generation = Superblock::read_from(&mut FileBackend::open(p).unwrap())
+        .unwrap()
+        .map_generation;
+
+    // tiap peta yang baru memiliki generasi yang berbeda
+    assert!({
+        s.alloc(64).unwrap();
+        s.sync().unwrap();
+
+        // storage di-drop tanpa unmount, layaknya ketika terjadi crash
+        drop(s);
+
+        let superblock = Superblock::read_from(&mut FileBackend::open(p).unwrap()).unwrap();
+        superblock.block_map.is_some() && superblock.map_generation == generation + 1
+    });
+}
+
+#[test]
+fn block_map_dropped_after_alloc() {
+    let (vol, mut blocks) = volume_with_blocks();
+    let mut s = mount(vol);
+
+    let address = s.alloc(64).unwrap();
+    blocks.push(Block { address, size: 64 });
+    blocks.sort_by_key(|b| b.address);
+
+    let mut vol = s.unmount().unwrap();
+
+    assert!(Superblock::read_from(&mut vol).unwrap().block_map.is_some());
+    assert!(mount(vol).blocks().unwrap() == blocks.as_slice());
+}
+
+#[test]
+fn unmount_full_volume() {
+    let mut s = util::memory_storage_sized(NEONDB_FILE_MIN_SIZE);
+
+    // sisakan ruang yang tidak cukup untuk menyimpan peta blok
+    while s.alloc(16).is_ok() {}
+
+    let count = s.blocks().unwrap().len();
+    let mut vol = s.unmount().unwrap();
+
+    assert!(Superblock::read_from(&mut vol).unwrap().block_map.is_none());
+    assert!(mount(vol).blocks().unwrap().len() == count);
+}
+
+#[test]
+fn block_map_is_reserved_block() {
+    let (mut vol, _) = volume_with_blocks();
+    let map = Superblock::read_from(&mut vol).unwrap().block_map.unwrap();
+
+    let mut s = mount(vol);
+
+    // head selalu berada di awal, diikuti oleh blok milik peta (yang
+    // alamatnya merupakan alamat sebenarnya, termasuk metadata)
+    assert!({
+        let reserved = s.reserved_blocks().unwrap();
+
+        reserved.len() == 2
+            && reserved[0].address == NEONDB_FILE_ALLOCATABLE_START
+            && reserved[1].address < map.address
+            && reserved[1].address + reserved[1].size > map.address
+    });
+
+    // begitu peta dibuang, hanya head yang tersisa
+    assert!({
+        s.alloc(64).unwrap();
+
+        s.reserved_blocks().unwrap().len() == 1
+    });
+}
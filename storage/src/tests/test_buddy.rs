@@ -0,0 +1,125 @@
+use super::*;
+use crate::{
+    AllocatorKind, Error, ErrorKind, GrowPolicy, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE,
+    NEONDB_FILE_MIN_SIZE,
+};
+
+fn init_storage(size: u64) -> Storage<MemoryBackend> {
+    let mut s = Storage::with_allocator(AllocatorKind::Buddy);
+    s.mount_memory(size).unwrap();
+
+    s
+}
+
+#[test]
+fn alloc_rounds_up_to_power_of_two() {
+    assert!({
+        let mut s = init_storage(NEONDB_FILE_DEFAULT_SIZE);
+        let address = s.alloc(100).unwrap();
+
+        // 100 byte data + 16 byte metadata, dibulatkan menjadi 128 byte
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 1 && blocks[0].address == address && blocks[0].size == 128 - 16
+    });
+}
+
+#[test]
+fn dealloc_merges_buddies() {
+    assert!({
+        let mut s = init_storage(NEONDB_FILE_MIN_SIZE);
+        let mut addresses = vec![];
+
+        for _ in 0..4 {
+            addresses.push(s.alloc(100).unwrap());
+        }
+
+        for address in addresses.iter().rev() {
+            s.dealloc(*address).unwrap();
+        }
+
+        // Blok-blok yang bersebelahan sudah digabungkan kembali, sehingga
+        // blok yang lebih besar dapat dialokasikan di alamat yang sama
+        s.blocks().unwrap().is_empty()
+            && s.alloc(400).unwrap() == addresses[3]
+            && s.alloc(200).unwrap() == addresses[1]
+    });
+}
+
+#[test]
+fn alloc_without_enough_space() {
+    assert!({
+        let mut s = init_storage(NEONDB_FILE_MIN_SIZE);
+
+        // Ruang kosong terbesar hanya setengah dari ruang yang dapat
+        // dialokasikan, karena head menempati bagian awal volume
+        let err = s.alloc(4096).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::VolumeNotEnoughSpace)
+            && err.expected() == Some(8192)
+            && err.actual() == Some(2048)
+    });
+}
+
+#[test]
+fn alloc_with_grow_policy() {
+    assert!({
+        let mut s = init_storage(NEONDB_FILE_MIN_SIZE);
+        s.set_grow_policy(GrowPolicy::Increment(1 << 12)).unwrap();
+
+        let address = s.alloc(NEONDB_FILE_MIN_SIZE as usize).unwrap();
+
+        s.blocks().unwrap()[0].address == address
+    });
+}
+
+#[test]
+fn remount_with_recorded_allocator() {
+    let mut s = init_storage(NEONDB_FILE_DEFAULT_SIZE);
+    let first = s.alloc(100).unwrap();
+    let second = s.alloc(1000).unwrap();
+    s.dealloc(first).unwrap();
+
+    // Storage dengan allocator bawaan tetap menggunakan buddy allocator
+    // untuk volume ini
+    let mut s = util::remount(s);
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 1 && blocks[0].address == second && blocks[0].size == 1024 - 16
+    });
+
+    assert!({
+        let address = s.alloc(100).unwrap();
+
+        address == first && s.blocks().unwrap()[0].size == 128 - 16
+    });
+}
+
+#[test]
+fn dealloc_at_illegal_address() {
+    assert!({
+        let mut s = init_storage(NEONDB_FILE_DEFAULT_SIZE);
+        let address = s.alloc(100).unwrap();
+
+        matches!(
+            s.dealloc(address + 8).map_err(Error::into_kind),
+            Err(ErrorKind::BlockNotFound)
+        )
+    });
+}
+
+#[test]
+fn free_blocks_sorted_by_address() {
+    assert!({
+        let mut s = init_storage(NEONDB_FILE_MIN_SIZE);
+        s.alloc(100).unwrap();
+
+        let free = s.free_blocks().unwrap();
+
+        free.windows(2)
+            .all(|w| w[0].address + w[0].size <= w[1].address)
+            && free.iter().map(|b| b.size).sum::<u64>() == s.stats().unwrap().free_bytes
+    });
+}
@@ -0,0 +1,168 @@
+use super::*;
+use crate::superblock::Superblock;
+use crate::{
+    AllocatorKind, ChecksumMode, Error, ErrorKind, MemoryBackend, Storage, VolumeBackend,
+    NEONDB_FILE_DEFAULT_SIZE,
+};
+
+fn init_storage(mode: ChecksumMode) -> Storage<MemoryBackend> {
+    let mut s = Storage::default();
+
+    s.set_checksum_mode(mode);
+    s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    s
+}
+
+// Membalik seluruh bit dari byte pada alamat yang diberikan, layaknya
+// kerusakan data di media penyimpanan
+fn corrupt(vol: &mut MemoryBackend, address: u64) {
+    let mut byte = [0u8];
+
+    vol.read_exact_at(address, &mut byte).unwrap();
+    vol.write_all_at(address, &[!byte[0]]).unwrap();
+}
+
+#[test]
+fn checksums_recorded_in_superblock() {
+    assert!({
+        let s = init_storage(ChecksumMode::Full);
+        let mut vol = util::remount(s).unmount().unwrap();
+
+        Superblock::read_from(&mut vol).unwrap().checksums == ChecksumMode::Full
+    });
+}
+
+#[test]
+fn mount_corrupted_metadata() {
+    assert!({
+        let mut s = init_storage(ChecksumMode::Metadata);
+
+        s.alloc(100).unwrap();
+        let address = s.alloc(200).unwrap();
+
+        let mut vol = s.unmount().unwrap();
+
+        // byte terakhir dari ukuran blok (metadata berukuran 24 byte)
+        corrupt(&mut vol, address - 17);
+
+        let mut s: Storage<MemoryBackend> = Storage::default();
+        let err = s.mount_backend(vol).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::ChecksumMismatch) && err.address() == Some(address)
+    });
+}
+
+#[test]
+fn read_corrupted_payload() {
+    let mut s = init_storage(ChecksumMode::Full);
+
+    let first = s.alloc(100).unwrap();
+    let second = s.alloc(100).unwrap();
+
+    s.write(first, &[1u8; 100]).unwrap();
+    s.write(second, &[2u8; 100]).unwrap();
+
+    let mut vol = s.unmount().unwrap();
+    corrupt(&mut vol, first + 50);
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend(vol).unwrap();
+
+    assert!({
+        let mut buff = [0u8; 10];
+        let err = s.read(first + 20, &mut buff).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::ChecksumMismatch) && err.address() == Some(first)
+    });
+
+    // blok lain tetap dapat dibaca
+    assert!({
+        let mut buff = [0u8; 100];
+        s.read(second, &mut buff).unwrap();
+
+        buff == [2u8; 100]
+    });
+}
+
+#[test]
+fn payload_not_checked_in_metadata_mode() {
+    assert!({
+        let mut s = init_storage(ChecksumMode::Metadata);
+
+        let address = s.alloc(100).unwrap();
+        s.write(address, &[1u8; 100]).unwrap();
+
+        let mut vol = s.unmount().unwrap();
+        corrupt(&mut vol, address);
+
+        let mut s: Storage<MemoryBackend> = Storage::default();
+        s.mount_backend(vol).unwrap();
+
+        let mut buff = [0u8; 1];
+        s.read(address, &mut buff).unwrap();
+
+        buff == [!1u8]
+    });
+}
+
+#[test]
+fn checksums_follow_changes() {
+    let mut s = init_storage(ChecksumMode::Full);
+    s.set_slab_threshold(64).unwrap();
+
+    let block = s.alloc(300).unwrap();
+    let object = s.alloc(32).unwrap();
+    let removed = s.alloc(500).unwrap();
+
+    s.write(block, &[1u8; 300]).unwrap();
+    s.write(object, &[2u8; 32]).unwrap();
+
+    let mut batch = s.batch().unwrap();
+    let other = batch.alloc(40).unwrap();
+
+    batch.write(other, &[3u8; 40]).unwrap();
+    batch.dealloc(removed).unwrap();
+    batch.commit().unwrap();
+
+    let block = s.realloc(block, 600).unwrap();
+    let remap = s.compact().unwrap();
+
+    let block = remap.get(&block).copied().unwrap_or(block);
+    let object = remap.get(&object).copied().unwrap_or(object);
+    let other = remap.get(&other).copied().unwrap_or(other);
+
+    let mut s = util::remount(s);
+
+    assert!({
+        let mut buff = [0u8; 300];
+        s.read(block, &mut buff).unwrap();
+
+        buff == [1u8; 300]
+    });
+
+    assert!({
+        let mut first = [0u8; 32];
+        let mut second = [0u8; 40];
+
+        s.read(object, &mut first).unwrap();
+        s.read(other, &mut second).unwrap();
+
+        first == [2u8; 32] && second == [3u8; 40]
+    });
+}
+
+#[test]
+fn checksums_unsupported_by_buddy() {
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::with_allocator(AllocatorKind::Buddy);
+        s.set_checksum_mode(ChecksumMode::Metadata);
+
+        let res = s.mount_memory(NEONDB_FILE_DEFAULT_SIZE);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnsupportedFeatures)
+        )
+    });
+}
@@ -0,0 +1,124 @@
+use super::*;
+use crate::{
+    AllocatorKind, Error, ErrorKind, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE,
+    NEONDB_FILE_MIN_SIZE,
+};
+
+// Volume dengan ruang kosong yang terpecah-pecah, beserta alamat dari
+// blok-blok yang masih digunakan (berisi byte dengan nilai indeksnya)
+fn fragmented_storage() -> (Storage<MemoryBackend>, Vec<u64>) {
+    let mut s = util::memory_storage_sized(NEONDB_FILE_MIN_SIZE);
+    let mut addresses = vec![];
+
+    for i in 0..6u8 {
+        let address = s.alloc(500).unwrap();
+        s.write(address, &[i; 500]).unwrap();
+
+        addresses.push(address);
+    }
+
+    for address in addresses.iter().step_by(2) {
+        s.dealloc(*address).unwrap();
+    }
+
+    let addresses = addresses.into_iter().skip(1).step_by(2).collect();
+    (s, addresses)
+}
+
+#[test]
+fn alloc_after_compaction() {
+    let (mut s, addresses) = fragmented_storage();
+
+    // total ruang kosong cukup, namun terpecah-pecah
+    assert!(matches!(
+        s.alloc(2000).map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotEnoughSpace)
+    ));
+
+    let remap = s.compact().unwrap();
+
+    assert!({ remap.len() == 3 && addresses.iter().all(|address| remap.contains_key(address)) });
+
+    assert!({
+        addresses.iter().enumerate().all(|(i, address)| {
+            let mut buff = [0u8; 500];
+            s.read(remap[address], &mut buff).unwrap();
+
+            buff == [i as u8 * 2 + 1; 500]
+        })
+    });
+
+    assert!(s.stats().unwrap().free_blocks == 1 && s.alloc(2000).is_ok());
+}
+
+#[test]
+fn compaction_persisted() {
+    let (mut s, _) = fragmented_storage();
+    let remap = s.compact().unwrap();
+
+    let mut s = util::remount(s);
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 3
+            && blocks
+                .iter()
+                .zip(remap.values())
+                .all(|(b, a)| b.address == *a)
+    });
+}
+
+#[test]
+fn compact_without_gaps() {
+    assert!({
+        let mut s = util::memory_storage();
+        s.alloc(100).unwrap();
+        s.alloc(100).unwrap();
+
+        s.compact().unwrap().is_empty()
+    });
+}
+
+#[test]
+fn compact_slab_objects() {
+    let mut s = util::memory_storage();
+    s.set_slab_threshold(64).unwrap();
+
+    let block = s.alloc(1000).unwrap();
+    let first = s.alloc(32).unwrap();
+    let second = s.alloc(32).unwrap();
+    s.write(second, &[7u8; 32]).unwrap();
+
+    s.dealloc(block).unwrap();
+    s.dealloc(first).unwrap();
+
+    let remap = s.compact().unwrap();
+
+    // hanya objek yang masih digunakan yang tercatat
+    assert!(remap.len() == 1 && remap.contains_key(&second));
+
+    let mut s = util::remount(s);
+    s.set_slab_threshold(64).unwrap();
+
+    assert!({
+        let mut buff = [0u8; 32];
+        s.read(remap[&second], &mut buff).unwrap();
+
+        buff == [7u8; 32] && s.alloc(32).unwrap() == remap[&second] - 32
+    });
+}
+
+#[test]
+fn compact_buddy_volume() {
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::with_allocator(AllocatorKind::Buddy);
+        s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+        let address = s.alloc(100).unwrap();
+        s.alloc(100).unwrap();
+        s.dealloc(address).unwrap();
+
+        s.compact().unwrap().is_empty()
+    });
+}
@@ -0,0 +1,104 @@
+use super::*;
+use crate::{
+    Error, ErrorKind, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE, NEONDB_FILE_MIN_SIZE,
+};
+
+use std::error::Error as _;
+use std::io;
+
+#[test]
+fn error_with_address() {
+    let mut s = util::memory_storage();
+    let address = s.alloc(64).unwrap();
+
+    assert!({
+        let err = s.write(address + 64, b"out of bounds").unwrap_err();
+
+        matches!(err.kind(), ErrorKind::BlockNotFound) && err.address() == Some(address + 64)
+    });
+
+    assert!({
+        let err = s.dealloc(address + 8).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::BlockNotFound) && err.address() == Some(address + 8)
+    });
+}
+
+#[test]
+fn error_with_sizes() {
+    let mut s: Storage<MemoryBackend> = Storage::default();
+
+    assert!({
+        let err = s.mount_memory(NEONDB_FILE_MIN_SIZE - 1).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::VolumeInvalidSize)
+            && err.expected() == Some(NEONDB_FILE_MIN_SIZE)
+            && err.actual() == Some(NEONDB_FILE_MIN_SIZE - 1)
+    });
+
+    let mut s = util::memory_storage();
+
+    assert!({
+        let err = s.alloc(NEONDB_FILE_DEFAULT_SIZE as usize).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::VolumeNotEnoughSpace) && err.expected() > err.actual()
+    });
+}
+
+#[test]
+fn error_with_path() {
+    let p = path_of!("tmp/storage/non_existing.neondb");
+
+    let mut s = Storage::new();
+    let err = s.mount(p).unwrap_err();
+
+    assert!(matches!(err.kind(), ErrorKind::VolumeNotFound) && err.path() == Some(p));
+    assert!(err.to_string().contains("non_existing.neondb"));
+}
+
+#[test]
+fn display_error() {
+    let mut s = util::memory_storage();
+    let err = s.dealloc(100).unwrap_err();
+
+    assert!(err.to_string() == "block not found (address 100)");
+}
+
+#[test]
+fn io_error_as_source() {
+    let err = crate::Error::from(io::Error::other("disk full"));
+
+    assert!(matches!(err.kind(), ErrorKind::Io(_)));
+    assert!(err.source().unwrap().to_string() == "disk full");
+}
+
+#[test]
+fn boxed_error() {
+    fn run() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut s = util::memory_storage();
+        s.dealloc(100)?;
+
+        Ok(())
+    }
+
+    assert!({
+        let err = run().unwrap_err();
+
+        err.downcast_ref::<crate::Error>()
+            .map(|err| matches!(err.kind(), ErrorKind::BlockNotFound))
+            .unwrap_or(false)
+    });
+}
+
+#[test]
+fn compare_error_kinds() {
+    assert!({
+        let err = Error::new(ErrorKind::BlockNotFound).with_address(100);
+        let io_err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+
+        *err.kind() == ErrorKind::BlockNotFound
+            && *err.kind() != ErrorKind::VolumeNotFound
+            && *io_err.kind() == ErrorKind::Io(io::ErrorKind::NotFound.into())
+            && *io_err.kind() != ErrorKind::Io(io::ErrorKind::Other.into())
+    });
+}
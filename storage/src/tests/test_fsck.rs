@@ -0,0 +1,324 @@
+use super::*;
+use crate::superblock::Superblock;
+use crate::{
+    AllocatorKind, Error, ErrorKind, Issue, MemoryBackend, Storage, VolumeBackend,
+    NEONDB_FILE_ALLOCATABLE_START, NEONDB_FILE_DEFAULT_SIZE,
+};
+
+use serial_test::serial;
+
+// Volume dengan 3 blok (masing-masing berukuran 100 byte) yang sudah
+// di-unmount, beserta alamat dari tiap blok
+fn init_volume() -> (MemoryBackend, Vec<u64>) {
+    let mut s = util::memory_storage();
+    let mut addresses = vec![];
+
+    for i in 0..3u8 {
+        let address = s.alloc(100).unwrap();
+        s.write(address, &[i; 100]).unwrap();
+
+        addresses.push(address);
+    }
+
+    (s.unmount().unwrap(), addresses)
+}
+
+// Metadata dari tiap blok berisi ukuran blok (8 byte) dan alamat blok
+// selanjutnya (8 byte), tepat sebelum alamat blok tersebut
+fn patch_size(vol: &mut MemoryBackend, address: u64, size: u64) {
+    vol.write_all_at(address - 16, &size.to_be_bytes()).unwrap();
+}
+
+fn patch_next(vol: &mut MemoryBackend, address: u64, next: u64) {
+    vol.write_all_at(address - 8, &next.to_be_bytes()).unwrap();
+}
+
+#[test]
+fn check_clean_volume() {
+    assert!({
+        let (mut vol, addresses) = init_volume();
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.is_clean()
+            && report
+                .blocks
+                .iter()
+                .map(|b| b.address)
+                .eq(addresses.into_iter())
+    });
+}
+
+#[test]
+fn check_zero_size_block() {
+    assert!({
+        let mut s = util::memory_storage();
+
+        s.alloc(100).unwrap();
+        let empty = s.alloc(0).unwrap();
+        s.alloc(100).unwrap();
+
+        let mut vol = s.unmount().unwrap();
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.is_clean()
+            && report.blocks.len() == 3
+            && report.blocks[1].address == empty
+            && report.blocks[1].size == 0
+    });
+}
+
+#[test]
+fn check_overlapping_blocks() {
+    assert!({
+        let (mut vol, addresses) = init_volume();
+
+        // blok kedua dimulai di tengah-tengah blok pertama
+        patch_next(&mut vol, addresses[0], addresses[0] + 50);
+
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.issues[0]
+            == Issue::Overlap {
+                index: 2,
+                address: addresses[0] + 50,
+                previous_end: addresses[0] + 100,
+            }
+            && report.blocks.len() == 1
+    });
+}
+
+#[test]
+fn check_cycle() {
+    assert!({
+        let (mut vol, addresses) = init_volume();
+        patch_next(&mut vol, addresses[1], addresses[0] - 16);
+
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.issues[0]
+            == Issue::Cycle {
+                index: 3,
+                address: addresses[0] - 16,
+            }
+    });
+}
+
+#[test]
+fn check_out_of_range_block() {
+    assert!({
+        let (mut vol, addresses) = init_volume();
+        patch_size(&mut vol, addresses[1], NEONDB_FILE_DEFAULT_SIZE);
+
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.issues[0]
+            == Issue::OutOfRange {
+                index: 2,
+                address: addresses[1] - 16,
+                size: NEONDB_FILE_DEFAULT_SIZE,
+            }
+    });
+}
+
+#[test]
+fn check_bad_head_size() {
+    assert!({
+        let (mut vol, _) = init_volume();
+
+        vol.write_all_at(NEONDB_FILE_ALLOCATABLE_START, &32u64.to_be_bytes())
+            .unwrap();
+
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.issues[0]
+            == Issue::BadHeadSize {
+                expected: 16,
+                actual: 32,
+            }
+            && report.blocks.is_empty()
+    });
+}
+
+#[test]
+fn check_orphaned_space() {
+    assert!({
+        let (mut vol, addresses) = init_volume();
+
+        // blok ketiga (beserta peta blok) tidak lagi dapat dijangkau,
+        // namun masih tercatat di peta blok
+        patch_next(&mut vol, addresses[1], 0);
+
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.issues.contains(&Issue::OrphanedSpace {
+            address: addresses[2] - 16,
+            size: 116,
+        }) && report
+            .issues
+            .iter()
+            .any(|issue| matches!(issue, Issue::StaleBlockMap { .. }))
+    });
+}
+
+#[test]
+fn check_orphaned_space_without_block_map() {
+    let (vol, addresses) = init_volume();
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend(vol).unwrap();
+    s.set_root("third", addresses[2]).unwrap();
+
+    let mut vol = s.unmount().unwrap();
+
+    let mut superblock = Superblock::read_from(&mut vol).unwrap();
+    superblock.block_map = None;
+    superblock.write_to(&mut vol).unwrap();
+
+    // blok ketiga hanya dapat dikenali melalui root yang menunjuk ke
+    // blok tersebut
+    patch_next(&mut vol, addresses[1], 0);
+
+    assert!({
+        let report = crate::check_backend(&mut vol).unwrap();
+
+        report.issues.contains(&Issue::OrphanedSpace {
+            address: addresses[2] - 16,
+            size: 116,
+        }) && report.blocks.len() == 2
+    });
+}
+
+#[test]
+fn repair_truncates_blocks() {
+    let (mut vol, addresses) = init_volume();
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend(vol).unwrap();
+    s.set_root("first", addresses[0]).unwrap();
+    s.set_root("third", addresses[2]).unwrap();
+
+    vol = s.unmount().unwrap();
+    patch_size(&mut vol, addresses[1], 8);
+
+    assert!({
+        let report = crate::repair_backend(&mut vol).unwrap();
+
+        report.is_repaired
+            && report.blocks.len() == 1
+            && report.issues.contains(&Issue::DanglingRoot {
+                name: "third".to_string(),
+                address: addresses[2],
+            })
+    });
+
+    // volume kembali dapat dimounting, dengan blok yang diselamatkan
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend(vol).unwrap();
+
+    assert!({
+        let mut buff = [9u8; 100];
+        s.read(addresses[0], &mut buff).unwrap();
+
+        s.blocks().unwrap().len() == 1
+            && buff == [0u8; 100]
+            && s.roots().unwrap().len() == 1
+            && s.root("first").unwrap() == Some(addresses[0])
+    });
+
+    assert!(s.alloc(100).unwrap() == addresses[1]);
+}
+
+#[test]
+fn repair_keeps_zero_size_block() {
+    let mut s = util::memory_storage();
+
+    let first = s.alloc(100).unwrap();
+    let empty = s.alloc(0).unwrap();
+    let last = s.alloc(100).unwrap();
+
+    let mut vol = s.unmount().unwrap();
+    patch_size(&mut vol, last, 8);
+
+    assert!({
+        let report = crate::repair_backend(&mut vol).unwrap();
+
+        report.is_repaired && report.blocks.len() == 2
+    });
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend(vol).unwrap();
+
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 2
+            && blocks[0].address == first
+            && blocks[1].address == empty
+            && blocks[1].size == 0
+    });
+}
+
+#[test]
+fn repair_clean_volume() {
+    assert!({
+        let (mut vol, _) = init_volume();
+        let bytes = vol.as_bytes().to_vec();
+
+        let report = crate::repair_backend(&mut vol).unwrap();
+
+        !report.is_repaired && vol.as_bytes() == bytes
+    });
+}
+
+#[test]
+fn check_unsupported_allocator() {
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::with_allocator(AllocatorKind::Buddy);
+        s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+        let mut vol = s.unmount().unwrap();
+        let res = crate::check_backend(&mut vol);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnsupportedFeatures)
+        )
+    });
+}
+
+#[test]
+#[serial]
+fn repair_volume_file() {
+    let p = path_of!("tmp/storage/repair.neondb");
+    let report_path = path_of!("tmp/storage/repair.neondb-repair.txt");
+
+    util::ensure_not_exists(p);
+    util::ensure_not_exists(report_path);
+
+    let address = {
+        let mut s = Storage::new();
+        s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+        let address = s.alloc(100).unwrap();
+        s.unmount().unwrap();
+
+        address
+    };
+
+    assert!({
+        use std::io::{prelude::*, SeekFrom};
+
+        let mut vol = std::fs::OpenOptions::new().write(true).open(p).unwrap();
+
+        vol.seek(SeekFrom::Start(address - 8)).unwrap();
+        vol.write_all(&1u64.to_be_bytes()).unwrap();
+
+        !crate::check(p).unwrap().is_clean()
+    });
+
+    assert!({
+        let report = crate::repair(p).unwrap();
+
+        report.is_repaired && report_path.exists() && crate::check(p).unwrap().is_clean()
+    });
+}
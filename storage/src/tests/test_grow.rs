@@ -0,0 +1,130 @@
+use super::*;
+use crate::{
+    Error, ErrorKind, GrowPolicy, MemoryBackend, Storage, VolumeBackend, NEONDB_FILE_MIN_SIZE,
+};
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+fn init_storage() -> Storage<MemoryBackend> {
+    util::memory_storage_sized(NEONDB_FILE_MIN_SIZE)
+}
+
+#[test]
+fn alloc_without_grow_policy() {
+    assert!({
+        let mut s = init_storage();
+
+        let res = s.alloc(NEONDB_FILE_MIN_SIZE as usize);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeNotEnoughSpace)
+        )
+    });
+}
+
+#[test]
+fn alloc_with_grow_policy() {
+    let increment = 1 << 12;
+
+    let mut s = init_storage();
+    s.set_grow_policy(GrowPolicy::Increment(increment)).unwrap();
+
+    assert!({
+        let first = s.alloc(64).unwrap();
+        let second = s.alloc(NEONDB_FILE_MIN_SIZE as usize).unwrap();
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 2 && blocks[0].address == first && blocks[1].address == second
+    });
+
+    let vol = s.unmount().unwrap();
+
+    // ukuran volume selalu kelipatan dari increment
+    assert!({
+        let len = vol.len().unwrap();
+
+        len > NEONDB_FILE_MIN_SIZE && len.is_multiple_of(increment)
+    });
+
+    // Ukuran baru harus tersimpan, sehingga volume tetap dapat dimounting
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::default();
+        s.mount_backend(vol).unwrap();
+
+        s.blocks().unwrap().len() == 2
+    });
+}
+
+#[test]
+fn grow_merges_with_last_unused_block() {
+    assert!({
+        let mut s = init_storage();
+        s.set_grow_policy(GrowPolicy::Increment(1 << 12)).unwrap();
+
+        let address = s.alloc(64).unwrap();
+
+        // sisa ruang kosong di akhir volume harus ikut terpakai, sehingga
+        // blok baru terletak tepat setelah blok sebelumnya
+        let next = s.alloc(NEONDB_FILE_MIN_SIZE as usize).unwrap();
+
+        next == address + 64 + 16
+    });
+}
+
+#[test]
+fn grow_policy_with_zero_increment() {
+    assert!({
+        let mut s = init_storage();
+        let res = s.set_grow_policy(GrowPolicy::Increment(0));
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::InvalidArgument)
+        ) && s.alloc(NEONDB_FILE_MIN_SIZE as usize).is_err()
+    });
+}
+
+#[test]
+fn grow_beyond_max_size() {
+    assert!({
+        let mut s = init_storage();
+        s.set_grow_policy(GrowPolicy::Increment(1 << 12)).unwrap();
+
+        // ukuran volume yang baru tidak dapat direpresentasikan
+        let res = s.alloc(usize::MAX - 8);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeNotEnoughSpace)
+        ) && s.alloc(64).is_ok()
+    });
+}
+
+#[test]
+fn grow_with_failing_backend() {
+    let fail_writes = Rc::new(Cell::new(false));
+    let vol = util::FaultyBackend {
+        inner: MemoryBackend::new(),
+        fail_writes: Rc::clone(&fail_writes),
+    };
+
+    let mut s = Storage::default();
+    s.mount_new_backend(vol, NEONDB_FILE_MIN_SIZE).unwrap();
+    s.set_grow_policy(GrowPolicy::Increment(1 << 12)).unwrap();
+
+    // kegagalan dari backend bukan berarti volume kehabisan ruang
+    assert!({
+        fail_writes.set(true);
+        let res = s.alloc(NEONDB_FILE_MIN_SIZE as usize);
+
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::Io(_)))
+    });
+
+    assert!({
+        fail_writes.set(false);
+
+        s.alloc(NEONDB_FILE_MIN_SIZE as usize).is_ok()
+    });
+}
@@ -1,5 +1,11 @@
 use super::*;
-use crate::{ErrorKind, Storage};
+use crate::mount;
+use crate::superblock::Superblock;
+use crate::wal::{Entry, Wal};
+use crate::{
+    AllocatorKind, ChecksumMode, Error, ErrorKind, MemoryBackend, Storage, VolumeBackend,
+    NEONDB_FILE_ALLOCATABLE_START, NEONDB_FILE_DEFAULT_SIZE, NEONDB_FILE_MIN_SIZE,
+};
 
 use serial_test::serial;
 
@@ -23,7 +29,10 @@ fn mount_non_existing_volume() {
         let mut s = Storage::new();
         let res = s.mount(path_of!("tmp/storage/non_existing.neondb"));
 
-        matches!(res, Err(ErrorKind::VolumeNotFound))
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeNotFound)
+        )
     });
 }
 
@@ -33,7 +42,10 @@ fn mount_invalid_ext() {
         let mut s = Storage::new();
         let res = s.mount(path_of!("tmp/storage/invalid.txt"));
 
-        matches!(res, Err(ErrorKind::VolumeInvalidExt))
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeInvalidExt)
+        )
     })
 }
 
@@ -43,7 +55,10 @@ fn mount_invalid_size() {
         let mut s = Storage::new();
         let res = s.mount(path_of!("tmp/storage/invalid.neondb"));
 
-        matches!(res, Err(ErrorKind::VolumeInvalidSize))
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeInvalidSize)
+        )
     });
 }
 
@@ -70,7 +85,10 @@ fn unmount_non_existing_volume() {
 
         let res = s.unmount();
 
-        matches!(res, Err(ErrorKind::VolumeNotFound))
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeNotFound)
+        )
     });
 }
 
@@ -83,7 +101,7 @@ fn mount_new_volume() {
         let mut s = Storage::new();
         util::ensure_not_exists(p);
 
-        let res = s.mount_new(p);
+        let res = s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE);
 
         res.is_ok()
     });
@@ -95,3 +113,502 @@ fn mount_new_volume() {
         s.blocks().unwrap().is_empty()
     });
 }
+
+#[test]
+#[serial]
+fn mount_new_sized_volume() {
+    let p = path_of!("tmp/storage/mount_new_sized.neondb");
+    let size = NEONDB_FILE_DEFAULT_SIZE * 2;
+
+    assert!({
+        let mut s = Storage::new();
+        util::ensure_not_exists(p);
+
+        s.mount_new(p, size).unwrap();
+
+        // lebih besar dari ukuran default, hanya bisa jika ukuran
+        // volume memang mengikuti ukuran yang diminta
+        s.alloc(NEONDB_FILE_DEFAULT_SIZE as usize).is_ok()
+    });
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount(p).unwrap();
+
+        p.metadata().unwrap().len() == size && s.blocks().unwrap().len() == 1
+    });
+}
+
+#[test]
+#[serial]
+fn mount_new_too_small_volume() {
+    assert!({
+        let p = path_of!("tmp/storage/mount_new_small.neondb");
+        util::ensure_not_exists(p);
+
+        let mut s = Storage::new();
+        let res = s.mount_new(p, NEONDB_FILE_MIN_SIZE - 1);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeInvalidSize)
+        ) && !p.exists()
+    });
+}
+
+#[test]
+#[serial]
+fn mount_size_mismatch() {
+    assert!({
+        let p = path_of!("tmp/storage/size_mismatch.neondb");
+        util::fresh_volume(p);
+
+        // ukuran file tidak lagi sesuai dengan yang tercatat di header
+        std::fs::OpenOptions::new()
+            .write(true)
+            .open(p)
+            .and_then(|f| f.set_len(NEONDB_FILE_DEFAULT_SIZE + 1))
+            .unwrap();
+
+        let mut s = Storage::new();
+        let res = s.mount(p);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeInvalidSize)
+        )
+    });
+}
+
+fn patch_volume(path: &std::path::Path, address: u64, bytes: &[u8]) {
+    use std::io::{prelude::*, SeekFrom};
+
+    let mut vol = std::fs::OpenOptions::new().write(true).open(path).unwrap();
+
+    vol.seek(SeekFrom::Start(address)).unwrap();
+    vol.write_all(bytes).unwrap();
+}
+
+#[test]
+#[serial]
+fn mount_unsupported_version() {
+    assert!({
+        let p = path_of!("tmp/storage/superblock.neondb");
+        util::fresh_volume(p);
+
+        // versi format berada tepat setelah NEONDB_FILE_MARK
+        patch_volume(p, 16, &99u32.to_be_bytes());
+
+        let mut s = Storage::new();
+        let res = s.mount(p);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnsupportedVersion)
+        )
+    });
+}
+
+#[test]
+#[serial]
+fn mount_unknown_allocator() {
+    assert!({
+        let p = path_of!("tmp/storage/superblock.neondb");
+        util::fresh_volume(p);
+
+        patch_volume(p, 20, &99u32.to_be_bytes());
+
+        let mut s = Storage::new();
+        let res = s.mount(p);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnknownAllocator)
+        )
+    });
+}
+
+#[test]
+#[serial]
+fn mount_unsupported_features() {
+    let p = path_of!("tmp/storage/superblock.neondb");
+
+    // fitur compat yang tidak dikenali tetap boleh dimounting
+    assert!({
+        util::fresh_volume(p);
+        patch_volume(p, 56, &(1u64 << 63).to_be_bytes());
+
+        let mut s = Storage::new();
+        s.mount(p).is_ok()
+    });
+
+    assert!({
+        util::fresh_volume(p);
+        patch_volume(p, 64, &(1u64 << 63).to_be_bytes());
+
+        let mut s = Storage::new();
+        let res = s.mount(p);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnsupportedFeatures)
+        )
+    });
+
+    // sedangkan fitur ro-compat yang tidak dikenali hanya boleh
+    // dimounting secara read-only
+    assert!({
+        util::fresh_volume(p);
+        patch_volume(p, 104, &(1u64 << 63).to_be_bytes());
+
+        let mut s = Storage::new();
+        let res = s.mount(p);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnsupportedFeatures)
+        )
+    });
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount_read_only(p).is_ok()
+    });
+}
+
+#[test]
+#[serial]
+fn volume_info_without_mounting() {
+    assert!({
+        let p = path_of!("tmp/storage/test.neondb");
+        util::fresh_volume(p);
+
+        let superblock = crate::volume_info(p).unwrap();
+
+        superblock.size == NEONDB_FILE_DEFAULT_SIZE && superblock.roots.is_empty()
+    });
+}
+
+#[test]
+fn volume_info_of_non_existing_volume() {
+    assert!({
+        let res = crate::volume_info(path_of!("tmp/storage/non_existing.neondb"));
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeNotFound)
+        )
+    });
+}
+
+#[test]
+#[serial]
+fn mount_locked_volume() {
+    assert!({
+        let p = path_of!("tmp/storage/test.neondb");
+        util::fresh_volume(p);
+
+        let mut first = Storage::new();
+        first.mount(p).unwrap();
+
+        let mut second = Storage::new();
+        let res = second.mount(p);
+
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::VolumeLocked))
+    });
+}
+
+#[test]
+#[serial]
+fn load_locked_volume() {
+    assert!({
+        let p = path_of!("tmp/storage/test.neondb");
+        util::fresh_volume(p);
+
+        let mut first = Storage::new();
+        first.mount(p).unwrap();
+
+        // pembacaan (read-only) juga membutuhkan lock, meskipun lock
+        // tersebut dapat dipegang bersamaan
+        let mut second: Storage<MemoryBackend> = Storage::default();
+        let res = second.load(p);
+
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::VolumeLocked))
+            && crate::volume_info(p).is_err()
+    });
+}
+
+#[test]
+#[serial]
+fn lock_released_on_unmount() {
+    assert!({
+        let p = path_of!("tmp/storage/test.neondb");
+        util::fresh_volume(p);
+
+        let mut first = Storage::new();
+        first.mount(p).unwrap();
+        first.unmount().unwrap();
+
+        let mut second = Storage::new();
+        second.mount(p).is_ok()
+    });
+}
+
+#[test]
+#[serial]
+fn lock_released_on_drop() {
+    assert!({
+        let p = path_of!("tmp/storage/test.neondb");
+        util::fresh_volume(p);
+
+        {
+            let mut first = Storage::new();
+            first.mount(p).unwrap();
+        }
+
+        let mut second = Storage::new();
+        second.mount(p).is_ok()
+    });
+}
+
+#[test]
+#[serial]
+fn mount_new_removes_volume_on_error() {
+    assert!({
+        let p = path_of!("tmp/storage/test_new_failed.neondb");
+        util::ensure_not_exists(p);
+
+        // buddy allocator tidak mendukung checksum, sehingga inisialisasi
+        // gagal setelah file volume dibuat
+        let mut s: Storage = Storage::with_allocator(AllocatorKind::Buddy);
+        s.set_checksum_mode(ChecksumMode::Full);
+
+        let res = s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeUnsupportedFeatures)
+        ) && !p.exists()
+            && !mount::wal_path(p).exists()
+    });
+}
+
+#[test]
+#[serial]
+fn mount_read_only_volume() {
+    let p = path_of!("tmp/storage/test_read_only.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = Storage::new();
+    s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"read-only").unwrap();
+    s.unmount().unwrap();
+
+    let bytes = std::fs::read(p).unwrap();
+
+    let mut first = Storage::new();
+    first.mount_read_only(p).unwrap();
+
+    // lock bersama dapat dipegang oleh beberapa pengguna sekaligus, namun
+    // tidak bersamaan dengan mounting biasa
+    assert!({
+        let mut second = Storage::new();
+        let mut third = Storage::new();
+
+        second.mount_read_only(p).is_ok()
+            && matches!(
+                third.mount(p).map_err(Error::into_kind),
+                Err(ErrorKind::VolumeLocked)
+            )
+    });
+
+    assert!({
+        let mut buff = [0u8; 9];
+        first.read(address, &mut buff).unwrap();
+
+        &buff == b"read-only" && first.blocks().unwrap().len() == 1
+    });
+
+    assert!({
+        let res = first.alloc(64);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeReadOnly)
+        ) && matches!(
+            first.write(address, b"x").map_err(Error::into_kind),
+            Err(ErrorKind::VolumeReadOnly)
+        ) && matches!(
+            first.dealloc(address).map_err(Error::into_kind),
+            Err(ErrorKind::VolumeReadOnly)
+        )
+    });
+
+    assert!({
+        first.unmount().unwrap();
+
+        std::fs::read(p).unwrap() == bytes
+    });
+}
+
+#[test]
+#[serial]
+fn mount_read_only_with_unrecovered_log() {
+    let p = path_of!("tmp/storage/test_read_only_log.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = Storage::new();
+    s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    let address = s.alloc(64).unwrap();
+    s.unmount().unwrap();
+
+    // transaksi yang tercatat di log, namun belum diterapkan ke volume
+    let mut wal = Wal::new(Box::new(mount::open_wal(p, false).unwrap()));
+    wal.append(&[Entry::Write {
+        address,
+        bytes: b"logged".to_vec(),
+    }])
+    .unwrap();
+
+    let bytes = std::fs::read(p).unwrap();
+    let log_len = mount::wal_path(p).metadata().unwrap().len();
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount_read_only(p).unwrap();
+
+        let mut buff = [0u8; 6];
+        s.read(address, &mut buff).unwrap();
+        s.unmount().unwrap();
+
+        // log hanya diterapkan di memori
+        &buff == b"logged"
+            && std::fs::read(p).unwrap() == bytes
+            && mount::wal_path(p).metadata().unwrap().len() == log_len
+    });
+}
+
+#[test]
+#[serial]
+fn mount_new_overwrite_volume() {
+    let p = path_of!("tmp/storage/test_overwrite.neondb");
+    util::ensure_not_exists(p);
+
+    // volume yang rusak tetap dapat ditimpa
+    std::fs::write(p, b"bukan volume").unwrap();
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount_new_overwrite(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+        s.alloc(64).unwrap();
+        s.unmount().unwrap();
+
+        crate::volume_info(p).unwrap().size == NEONDB_FILE_DEFAULT_SIZE
+    });
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount_new_overwrite(p, NEONDB_FILE_MIN_SIZE).unwrap();
+
+        s.blocks().unwrap().is_empty()
+    });
+}
+
+#[test]
+#[serial]
+fn mount_new_overwrite_keeps_old_volume_on_error() {
+    let p = path_of!("tmp/storage/test_overwrite_kept.neondb");
+    util::ensure_not_exists(p);
+
+    let mut first = Storage::new();
+    first.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    // volume yang sedang dimounting tidak boleh ditimpa
+    assert!({
+        let mut second = Storage::new();
+        let res = second.mount_new_overwrite(p, NEONDB_FILE_DEFAULT_SIZE);
+
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::VolumeLocked))
+    });
+
+    first.unmount().unwrap();
+
+    // begitu pula jika ukuran volume baru tidak valid
+    assert!({
+        let mut s = Storage::new();
+        let res = s.mount_new_overwrite(p, NEONDB_FILE_MIN_SIZE - 1);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeInvalidSize)
+        ) && crate::volume_info(p).unwrap().size == NEONDB_FILE_DEFAULT_SIZE
+    });
+}
+
+// Volume tanpa peta blok (sehingga seluruh blok harus dipindai ketika
+// mounting), dimana metadata dari blok kedua diganti dengan size dan
+// alamat blok berikutnya yang diberikan
+fn volume_with_corrupted_block(size: u64, next_address: Option<u64>) -> MemoryBackend {
+    let mut s = util::memory_storage();
+
+    s.alloc(64).unwrap();
+    let address = s.alloc(64).unwrap() - 16;
+    s.alloc(64).unwrap();
+
+    let mut vol = s.unmount().unwrap();
+
+    let mut superblock = Superblock::read_from(&mut vol).unwrap();
+    superblock.block_map = None;
+    superblock.write_to(&mut vol).unwrap();
+
+    let next_address = next_address.unwrap_or(address);
+
+    vol.write_all_at(address, &size.to_be_bytes()).unwrap();
+    vol.write_all_at(address + 8, &next_address.to_be_bytes())
+        .unwrap();
+
+    vol
+}
+
+fn mount_corrupted(vol: MemoryBackend) -> bool {
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    let res = s.mount_backend(vol);
+
+    matches!(
+        res.map_err(Error::into_kind),
+        Err(ErrorKind::VolumeCorrupted)
+    )
+}
+
+#[test]
+fn mount_block_pointing_to_itself() {
+    // blok berukuran 0 yang menunjuk ke dirinya sendiri
+    assert!(mount_corrupted(volume_with_corrupted_block(0, None)));
+}
+
+#[test]
+fn mount_block_smaller_than_metadata() {
+    assert!(mount_corrupted(volume_with_corrupted_block(8, Some(0))));
+}
+
+#[test]
+fn mount_block_pointing_backwards() {
+    let vol = volume_with_corrupted_block(80, Some(NEONDB_FILE_ALLOCATABLE_START));
+
+    assert!(mount_corrupted(vol));
+}
+
+#[test]
+fn mount_block_beyond_volume() {
+    assert!(mount_corrupted(volume_with_corrupted_block(
+        NEONDB_FILE_DEFAULT_SIZE,
+        Some(0)
+    )));
+    assert!(mount_corrupted(volume_with_corrupted_block(
+        u64::MAX,
+        Some(0)
+    )));
+}
@@ -1,34 +1,22 @@
 use super::*;
-use crate::{ErrorKind, Storage};
+use crate::{Error, ErrorKind, MemoryBackend, Storage, VolumeBackend};
 
-use serial_test::serial;
-
-use std::fs::OpenOptions;
-use std::io::{prelude::*, SeekFrom};
+fn init_storage() -> Storage<MemoryBackend> {
+    util::memory_storage()
+}
 
-fn init_storage() -> Storage {
-    util::fresh_volume(path_of!("tmp/storage/ops.neondb"));
+// Menulis langsung ke volume, tanpa melalui Storage
+fn write_ones(s: Storage<MemoryBackend>, address: u64, size: usize) -> Storage<MemoryBackend> {
+    let mut s = s;
+    let mut vol = s.unmount().unwrap();
 
-    let mut s = Storage::new();
-    s.mount(path_of!("tmp/storage/ops.neondb")).unwrap();
+    vol.write_at(address, &vec![1u8; size]).unwrap();
+    s.mount_backend(vol).unwrap();
 
     s
 }
 
-fn write_ones(address: u64, size: usize) {
-    let mut vol = OpenOptions::new()
-        .read(true)
-        .write(true)
-        .open(path_of!("tmp/storage/ops.neondb"))
-        .unwrap();
-
-    vol.seek(SeekFrom::Start(address))
-        .and_then(|_| vol.write(&vec![1u8; size]))
-        .unwrap();
-}
-
 #[test]
-#[serial]
 fn read_block_bytes() {
     let mut s = init_storage();
     let mut buff = [0u8; 16];
@@ -40,7 +28,7 @@ fn read_block_bytes() {
         matches!(res, Ok(n) if n == 16)
     });
 
-    write_ones(address, 16);
+    let mut s = write_ones(s, address, 16);
 
     assert!({
         let res = s.read(address, &mut buff);
@@ -54,7 +42,6 @@ fn read_block_bytes() {
 }
 
 #[test]
-#[serial]
 fn read_at_illegal_address() {
     assert!({
         let mut s = init_storage();
@@ -63,7 +50,7 @@ fn read_at_illegal_address() {
         // alamat acak
         let res = s.read(234653, &mut buff);
 
-        matches!(res, Err(ErrorKind::BlockNotFound))
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::BlockNotFound))
     });
 
     assert!({
@@ -79,14 +66,13 @@ fn read_at_illegal_address() {
 }
 
 #[test]
-#[serial]
 fn read_truncated() {
     assert!({
         let mut s = init_storage();
         let mut buff = [0u8; 64];
 
         let address = s.alloc(64).unwrap();
-        write_ones(address, 64);
+        let mut s = write_ones(s, address, 64);
 
         // membaca mulai dari posisi tengah blok
         let res = s.read(address + 32, &mut buff);
@@ -107,8 +93,8 @@ fn read_truncated() {
         // berisikan byte 1
         let address_one = s.alloc(64).unwrap();
         let address_two = s.alloc(64).unwrap();
-        write_ones(address_one, 64);
-        write_ones(address_two, 64);
+        let s = write_ones(s, address_one, 64);
+        let mut s = write_ones(s, address_two, 64);
 
         let res = s.read(address_one + 32, &mut buff);
 
@@ -127,14 +113,13 @@ fn read_truncated() {
  */
 
 #[test]
-#[serial]
 fn write_block_bytes() {
     let mut s = init_storage();
     let text = "some text here";
     let address = s.alloc(64).unwrap();
 
     assert!({
-        let res = s.write(address, &text.as_bytes());
+        let res = s.write(address, text.as_bytes());
 
         matches!(res, Ok(n) if n == text.len())
     });
@@ -143,26 +128,24 @@ fn write_block_bytes() {
         let mut buff = vec![0u8; text.len()];
         s.read(address, &mut buff).unwrap();
 
-        &buff == &text.as_bytes()
+        buff == text.as_bytes()
     });
 }
 
 #[test]
-#[serial]
 fn write_at_illegal_address() {
     assert!({
         let mut s = init_storage();
         let text = "some random text";
 
         // alamat acak
-        let res = s.write(234653, &text.as_bytes());
+        let res = s.write(234653, text.as_bytes());
 
-        matches!(res, Err(ErrorKind::BlockNotFound))
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::BlockNotFound))
     });
 }
 
 #[test]
-#[serial]
 fn write_truncated() {
     let mut s = init_storage();
     let text = "truncated string";
@@ -171,7 +154,7 @@ fn write_truncated() {
     let address = s.alloc(si).unwrap();
 
     assert!({
-        let res = s.write(address, &text.as_bytes());
+        let res = s.write(address, text.as_bytes());
 
         matches!(res, Ok(n) if n == si)
     });
@@ -180,6 +163,43 @@ fn write_truncated() {
         let mut buff = vec![0u8; si];
         s.read(address, &mut buff).unwrap();
 
-        buff == &text.as_bytes()[..si]
+        buff == text.as_bytes()[..si]
     });
 }
+
+#[test]
+fn operations_without_volume() {
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    let mut buff = [0u8; 16];
+
+    assert!(matches!(
+        s.read(100, &mut buff).map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotFound)
+    ));
+    assert!(matches!(
+        s.write(100, &buff).map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotFound)
+    ));
+    assert!(matches!(
+        s.alloc(16).map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotFound)
+    ));
+    assert!(matches!(
+        s.dealloc(100).map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotFound)
+    ));
+    assert!(matches!(
+        s.sync().map_err(Error::into_kind),
+        Err(ErrorKind::VolumeNotFound)
+    ));
+}
+
+#[test]
+fn dealloc_at_illegal_address() {
+    let mut s = init_storage();
+
+    assert!(matches!(
+        s.dealloc(1).map_err(Error::into_kind),
+        Err(ErrorKind::BlockNotFound)
+    ));
+}
@@ -0,0 +1,108 @@
+use super::*;
+use crate::{AllocatorKind, Error, ErrorKind, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+
+fn init_storage() -> Storage<MemoryBackend> {
+    util::memory_storage()
+}
+
+#[test]
+fn shrink_in_place() {
+    let mut s = init_storage();
+    let address = s.alloc(200).unwrap();
+    let next = s.alloc(100).unwrap();
+
+    assert!({ s.realloc(address, 50).unwrap() == address && s.blocks().unwrap()[0].size == 50 });
+
+    // sisa blok dapat dialokasikan kembali
+    assert!({
+        let freed = s.alloc(100).unwrap();
+
+        address < freed && freed < next
+    });
+}
+
+#[test]
+fn grow_in_place() {
+    let mut s = init_storage();
+    let first = s.alloc(100).unwrap();
+    let second = s.alloc(100).unwrap();
+    s.alloc(100).unwrap();
+
+    s.dealloc(second).unwrap();
+
+    assert!({ s.realloc(first, 200).unwrap() == first && s.blocks().unwrap()[0].size == 200 });
+
+    // ruang kosong yang tersisa tidak cukup untuk blok sebesar ini
+    assert!(s.realloc(first, 300).unwrap() != first);
+}
+
+#[test]
+fn grow_by_moving() {
+    let mut s = init_storage();
+    let address = s.alloc(100).unwrap();
+    let next = s.alloc(100).unwrap();
+
+    s.write(address, &[1u8; 100]).unwrap();
+
+    let new_address = s.realloc(address, 500).unwrap();
+
+    assert!({
+        let mut buff = [0u8; 100];
+        s.read(new_address, &mut buff).unwrap();
+
+        new_address > next && buff == [1u8; 100]
+    });
+
+    // blok lama sudah didealokasi
+    assert!({
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 2 && blocks[0].address == next && blocks[1].size == 500
+    });
+}
+
+#[test]
+fn realloc_slab_object() {
+    let mut s = init_storage();
+    s.set_slab_threshold(64).unwrap();
+
+    let address = s.alloc(30).unwrap();
+    s.write(address, &[3u8; 30]).unwrap();
+
+    // ukuran setelah dibulatkan tetap sama
+    assert!(s.realloc(address, 32).unwrap() == address);
+
+    assert!({
+        let new_address = s.realloc(address, 40).unwrap();
+
+        let mut buff = [0u8; 32];
+        s.read(new_address, &mut buff).unwrap();
+
+        new_address != address && buff[..30] == [3u8; 30] && s.blocks().unwrap().len() == 1
+    });
+}
+
+#[test]
+fn shrink_buddy_block() {
+    assert!({
+        let mut s: Storage<MemoryBackend> = Storage::with_allocator(AllocatorKind::Buddy);
+        s.mount_memory(NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+        let address = s.alloc(1000).unwrap();
+
+        s.realloc(address, 100).unwrap() == address && s.blocks().unwrap()[0].size == 128 - 16
+    });
+}
+
+#[test]
+fn realloc_at_illegal_address() {
+    assert!({
+        let mut s = init_storage();
+        let address = s.alloc(100).unwrap();
+
+        matches!(
+            s.realloc(address + 8, 200).map_err(Error::into_kind),
+            Err(ErrorKind::BlockNotFound)
+        )
+    });
+}
@@ -0,0 +1,132 @@
+use super::*;
+use crate::{Error, ErrorKind, Storage};
+
+#[test]
+fn roots_persisted() {
+    let mut s = util::memory_storage();
+
+    let catalog = s.alloc(100).unwrap();
+    let index = s.alloc(100).unwrap();
+
+    s.set_root("catalog", catalog).unwrap();
+    s.set_root("index", index).unwrap();
+    s.set_root("index", catalog).unwrap();
+
+    let s = util::remount(s);
+
+    assert!({
+        s.root("catalog").unwrap() == Some(catalog)
+            && s.root("index").unwrap() == Some(catalog)
+            && s.root("lainnya").unwrap().is_none()
+            && s.roots().unwrap().len() == 2
+    });
+}
+
+#[test]
+fn remove_root() {
+    let mut s = util::memory_storage();
+    let address = s.alloc(100).unwrap();
+
+    s.set_root("catalog", address).unwrap();
+
+    assert!({
+        s.remove_root("catalog").unwrap() == Some(address)
+            && s.remove_root("catalog").unwrap().is_none()
+    });
+
+    let s = util::remount(s);
+
+    assert!(s.roots().unwrap().is_empty());
+}
+
+#[test]
+fn invalid_roots() {
+    let mut s = util::memory_storage();
+    let address = s.alloc(100).unwrap();
+
+    assert!(matches!(
+        s.set_root("", address).map_err(Error::into_kind),
+        Err(ErrorKind::InvalidRootName)
+    ));
+
+    assert!(matches!(
+        s.set_root("nama-root-yang-terlalu-panjang", address)
+            .map_err(Error::into_kind),
+        Err(ErrorKind::InvalidRootName)
+    ));
+
+    assert!(matches!(
+        s.set_root("catalog", address + 8).map_err(Error::into_kind),
+        Err(ErrorKind::BlockNotFound)
+    ));
+
+    assert!({
+        for i in 0..64 {
+            s.set_root(&format!("root-{}", i), address).unwrap();
+        }
+
+        matches!(
+            s.set_root("catalog", address).map_err(Error::into_kind),
+            Err(ErrorKind::RootTableFull)
+        )
+    });
+}
+
+#[test]
+fn roots_follow_moved_blocks() {
+    let mut s = util::memory_storage();
+
+    let first = s.alloc(100).unwrap();
+    let second = s.alloc(100).unwrap();
+
+    s.set_root("first", first).unwrap();
+    s.set_root("second", second).unwrap();
+
+    let first = s.realloc(first, 1000).unwrap();
+    assert!(s.root("first").unwrap() == Some(first));
+
+    let remap = s.compact().unwrap();
+    let s = util::remount(s);
+
+    assert!({
+        s.root("first").unwrap() == Some(remap[&first])
+            && s.root("second").unwrap() == Some(remap[&second])
+    });
+}
+
+#[test]
+fn set_root_in_batch() {
+    let mut s = util::memory_storage();
+
+    assert!({
+        let mut batch = s.batch().unwrap();
+        let address = batch.alloc(100).unwrap();
+        batch.set_root("catalog", address).unwrap();
+
+        // batch tidak di-commit
+        drop(batch);
+
+        s.root("catalog").unwrap().is_none()
+    });
+
+    assert!({
+        let mut batch = s.batch().unwrap();
+        let address = batch.alloc(100).unwrap();
+        batch.set_root("catalog", address).unwrap();
+        batch.commit().unwrap();
+
+        s.root("catalog").unwrap() == Some(address)
+    });
+}
+
+#[test]
+fn roots_without_volume() {
+    assert!({
+        let s = Storage::new();
+
+        matches!(
+            s.root("catalog").map_err(Error::into_kind),
+            Err(ErrorKind::VolumeNotFound)
+        )
+    });
+}
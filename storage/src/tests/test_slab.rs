@@ -0,0 +1,175 @@
+use super::*;
+use crate::superblock::Superblock;
+use crate::{Error, ErrorKind, MemoryBackend, Storage, NEONDB_FILE_DEFAULT_SIZE};
+
+fn init_storage() -> Storage<MemoryBackend> {
+    let mut s = util::memory_storage();
+    s.set_slab_threshold(128).unwrap();
+
+    s
+}
+
+#[test]
+fn alloc_objects_in_same_page() {
+    assert!({
+        let mut s = init_storage();
+
+        let first = s.alloc(24).unwrap();
+        let second = s.alloc(24).unwrap();
+        let blocks = s.blocks().unwrap();
+
+        // objek-objek tidak memiliki metadata, sehingga saling berdempetan
+        second == first + 24
+            && blocks.len() == 2
+            && blocks[0].address == first
+            && blocks[0].size == 24
+    });
+}
+
+#[test]
+fn alloc_above_threshold() {
+    assert!({
+        let mut s = init_storage();
+
+        let object = s.alloc(100).unwrap();
+        let block = s.alloc(200).unwrap();
+        let blocks = s.blocks().unwrap();
+
+        // objek dengan ukuran 100 dibulatkan menjadi 104 byte
+        blocks.len() == 2
+            && blocks.iter().any(|b| b.address == object && b.size == 104)
+            && blocks.iter().any(|b| b.address == block && b.size == 200)
+    });
+}
+
+#[test]
+fn write_within_object() {
+    assert!({
+        let mut s = init_storage();
+
+        let first = s.alloc(32).unwrap();
+        let second = s.alloc(32).unwrap();
+
+        // penulisan tidak boleh melewati batas objek
+        s.write(first, &[1u8; 40]).unwrap() == 32 && s.write(second, &[2u8; 8]).unwrap() == 8
+    });
+}
+
+#[test]
+fn dealloc_objects() {
+    let mut s = init_storage();
+
+    let first = s.alloc(64).unwrap();
+    let second = s.alloc(64).unwrap();
+
+    assert!({
+        s.dealloc(first).unwrap();
+
+        // slot yang sudah dibebaskan dapat digunakan kembali
+        s.alloc(64).unwrap() == first
+    });
+
+    assert!({
+        let res = s.dealloc(second + 8);
+
+        matches!(res.map_err(Error::into_kind), Err(ErrorKind::BlockNotFound))
+    });
+
+    assert!({
+        s.dealloc(first).unwrap();
+        s.dealloc(second).unwrap();
+
+        s.blocks().unwrap().is_empty()
+    });
+
+    // halaman slab yang sudah kosong dikembalikan ke allocator
+    let vol = s.unmount().unwrap();
+    assert!({
+        let mut vol = vol;
+
+        Superblock::read_from(&mut vol).unwrap().slab_root.is_none()
+    });
+}
+
+#[test]
+fn fill_multiple_pages() {
+    assert!({
+        let mut s = init_storage();
+        let mut addresses = vec![];
+
+        // tiap halaman hanya dapat menampung 31 objek berukuran 128 byte
+        for _ in 0..100 {
+            addresses.push(s.alloc(128).unwrap());
+        }
+
+        for address in addresses.iter().step_by(2) {
+            s.dealloc(*address).unwrap();
+        }
+
+        let blocks = s.blocks().unwrap();
+
+        blocks.len() == 50
+            && blocks
+                .iter()
+                .zip(addresses.iter().skip(1).step_by(2))
+                .all(|(b, address)| b.address == *address)
+    });
+}
+
+#[test]
+fn slab_pages_persisted() {
+    let mut s = init_storage();
+    let mut addresses = vec![];
+
+    for i in 0..40u8 {
+        let address = s.alloc(48).unwrap();
+        s.write(address, &[i; 48]).unwrap();
+
+        addresses.push(address);
+    }
+    s.dealloc(addresses[3]).unwrap();
+
+    let mut s = util::remount(s);
+    s.set_slab_threshold(128).unwrap();
+
+    assert!({
+        let mut buff = [0u8; 48];
+        s.read(addresses[39], &mut buff).unwrap();
+
+        s.blocks().unwrap().len() == 39 && buff == [39u8; 48]
+    });
+
+    assert!(s.alloc(48).unwrap() == addresses[3]);
+}
+
+#[test]
+fn mount_corrupted_slab_root() {
+    assert!({
+        let mut s = init_storage();
+        s.alloc(24).unwrap();
+
+        let mut vol = s.unmount().unwrap();
+        let mut superblock = Superblock::read_from(&mut vol).unwrap();
+
+        superblock.slab_root = Some(NEONDB_FILE_DEFAULT_SIZE / 2);
+        superblock.write_to(&mut vol).unwrap();
+
+        let mut s: Storage<MemoryBackend> = Storage::default();
+        let res = s.mount_backend(vol);
+
+        matches!(
+            res.map_err(Error::into_kind),
+            Err(ErrorKind::VolumeCorrupted)
+        )
+    });
+}
+
+#[test]
+fn slab_threshold_too_large() {
+    assert!({
+        let mut s = util::memory_storage();
+        let err = s.set_slab_threshold(512).unwrap_err();
+
+        matches!(err.kind(), ErrorKind::InvalidArgument) && err.actual() == Some(512)
+    });
+}
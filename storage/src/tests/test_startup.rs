@@ -1,23 +1,15 @@
 use super::*;
-use crate::{alloc::Block, Storage};
+use crate::{alloc::Block, MemoryBackend, Storage};
 
-use serial_test::serial;
-
-fn init_storage() -> Storage {
-    util::fresh_volume(path_of!("tmp/storage/startup.neondb"));
-
-    let mut s = Storage::new();
-    s.mount(path_of!("tmp/storage/startup.neondb")).unwrap();
-
-    s
+fn init_storage() -> Storage<MemoryBackend> {
+    util::memory_storage()
 }
 
 #[test]
-#[serial]
 fn startup_with_blocks_exist() {
-    let blocks = {
-        let mut s = init_storage();
+    let mut s = init_storage();
 
+    let blocks = {
         for _ in 0..5 {
             s.alloc(64).unwrap();
         }
@@ -27,19 +19,15 @@ fn startup_with_blocks_exist() {
             .iter()
             .map(|b| Block { ..*b })
             .collect::<Vec<Block>>()
-    }; // drop s terlebih dulu
+    };
 
-    assert!({
-        let mut s = Storage::new();
-        s.mount(path_of!("tmp/storage/startup.neondb")).unwrap();
+    let mut s = util::remount(s);
 
-        blocks == s.blocks().unwrap()
-    });
+    assert!(blocks == s.blocks().unwrap());
 
     // Coba alokasi kembali setelah startup
     assert!({
-        let mut s = Storage::new();
-        s.mount(path_of!("tmp/storage/startup.neondb")).unwrap();
+        let mut s = util::remount(s);
 
         let address = s.alloc(64).unwrap();
 
@@ -48,10 +36,10 @@ fn startup_with_blocks_exist() {
 }
 
 #[test]
-#[serial]
 fn startup_after_dealloc_blocks() {
+    let mut s = init_storage();
+
     let blocks = {
-        let mut s = init_storage();
         let mut dealloc_addresses = vec![];
 
         for i in 0..10 {
@@ -72,19 +60,15 @@ fn startup_after_dealloc_blocks() {
             .iter()
             .map(|b| Block { ..*b })
             .collect::<Vec<Block>>()
-    }; // drop s terlebih dulu
+    };
 
-    assert!({
-        let mut s = Storage::new();
-        s.mount(path_of!("tmp/storage/startup.neondb")).unwrap();
+    let mut s = util::remount(s);
 
-        blocks == s.blocks().unwrap()
-    });
+    assert!(blocks == s.blocks().unwrap());
 
     // Coba alokasi kembali setelah startup
     assert!({
-        let mut s = Storage::new();
-        s.mount(path_of!("tmp/storage/startup.neondb")).unwrap();
+        let mut s = util::remount(s);
 
         let address = s.alloc(64 * 9).unwrap();
 
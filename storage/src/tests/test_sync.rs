@@ -0,0 +1,172 @@
+use super::*;
+use crate::mount;
+use crate::{MemoryBackend, Storage, SyncPolicy, VolumeBackend, NEONDB_FILE_DEFAULT_SIZE};
+
+use serial_test::serial;
+
+use std::cell::Cell;
+use std::io;
+use std::rc::Rc;
+use std::time::Duration;
+
+// Backend di memori yang menghitung berapa kali sync dipanggil
+struct CountingBackend {
+    inner: MemoryBackend,
+    syncs: Rc<Cell<usize>>,
+}
+
+impl VolumeBackend for CountingBackend {
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+        self.inner.read_at(offset, buff)
+    }
+
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize> {
+        self.inner.write_at(offset, buff)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        self.inner.len()
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.inner.set_len(len)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.syncs.set(self.syncs.get() + 1);
+        self.inner.sync()
+    }
+}
+
+fn init_storage(policy: SyncPolicy) -> (Storage<CountingBackend>, Rc<Cell<usize>>) {
+    let syncs = Rc::new(Cell::new(0));
+    let vol = CountingBackend {
+        inner: MemoryBackend::new(),
+        syncs: Rc::clone(&syncs),
+    };
+
+    let mut s = Storage::default();
+    s.set_sync_policy(policy);
+    s.mount_new_backend(vol, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    syncs.set(0);
+    (s, syncs)
+}
+
+#[test]
+fn sync_always() {
+    let (mut s, syncs) = init_storage(SyncPolicy::Always);
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"durable").unwrap();
+
+    assert!(syncs.get() == 2);
+}
+
+#[test]
+fn sync_on_commit() {
+    let (mut s, syncs) = init_storage(SyncPolicy::OnCommit);
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"not yet").unwrap();
+
+    assert!(syncs.get() == 0);
+
+    assert!({
+        let mut batch = s.batch().unwrap();
+        batch.write(address, b"durable").unwrap();
+        batch.commit().unwrap();
+
+        syncs.get() == 1
+    });
+}
+
+#[test]
+fn sync_periodic() {
+    let (mut s, syncs) = init_storage(SyncPolicy::Periodic(Duration::from_secs(3600)));
+
+    s.alloc(64).unwrap();
+    assert!(syncs.get() == 0);
+
+    s.set_sync_policy(SyncPolicy::Periodic(Duration::from_secs(0)));
+
+    s.alloc(64).unwrap();
+    assert!(syncs.get() == 1);
+}
+
+#[test]
+fn sync_never() {
+    let (mut s, syncs) = init_storage(SyncPolicy::Never);
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"later").unwrap();
+
+    assert!(syncs.get() == 0);
+
+    // seluruh perubahan sebelumnya disimpan sekaligus
+    assert!({
+        s.sync().unwrap();
+        s.sync().unwrap();
+
+        syncs.get() == 1
+    });
+
+    // unmount selalu menyimpan perubahan secara permanen
+    assert!({
+        s.dealloc(address).unwrap();
+        s.unmount().unwrap();
+
+        syncs.get() == 2
+    });
+}
+
+#[test]
+#[serial]
+fn unsynced_changes_kept_after_unmount() {
+    let p = path_of!("tmp/storage/sync_never.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = Storage::new();
+    s.set_sync_policy(SyncPolicy::Never);
+    s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"kept").unwrap();
+    s.unmount().unwrap();
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount(p).unwrap();
+
+        let mut buff = [0u8; 4];
+        s.read(address, &mut buff).unwrap();
+
+        &buff == b"kept"
+    });
+}
+
+#[test]
+#[serial]
+fn unsynced_changes_still_logged() {
+    let p = path_of!("tmp/storage/sync_never_wal.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = Storage::new();
+    s.set_sync_policy(SyncPolicy::Never);
+    s.mount_new(p, NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    // transaksi tetap dicatat di log, meskipun belum disimpan secara
+    // permanen
+    assert!({
+        let address = s.alloc(64).unwrap();
+        s.write(address, b"logged").unwrap();
+
+        mount::wal_path(p).metadata().unwrap().len() > 0
+    });
+
+    assert!({
+        s.sync().unwrap();
+
+        mount::wal_path(p).metadata().unwrap().len() == 0
+    });
+}
@@ -0,0 +1,252 @@
+use super::*;
+use crate::mount;
+use crate::wal::{Entry, Journal, Wal};
+use crate::{MemoryBackend, Storage, VolumeBackend};
+
+use serial_test::serial;
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+// Volume berisi satu blok, beserta alamat dari blok tersebut
+fn volume_with_block() -> (MemoryBackend, u64) {
+    let mut s = util::memory_storage();
+    let address = s.alloc(64).unwrap();
+
+    (s.unmount().unwrap(), address)
+}
+
+fn write_entry(address: u64, bytes: &[u8]) -> Entry {
+    Entry::Write {
+        address,
+        bytes: bytes.to_vec(),
+    }
+}
+
+fn read_bytes(s: &mut Storage<MemoryBackend>, address: u64, len: usize) -> Vec<u8> {
+    let mut buff = vec![0u8; len];
+    s.read(address, &mut buff).unwrap();
+
+    buff
+}
+
+#[test]
+fn replay_committed_transaction() {
+    let (vol, address) = volume_with_block();
+
+    // crash tepat setelah transaksi tercatat di log, namun sebelum
+    // transaksi tersebut diterapkan ke volume
+    let mut wal = Wal::new(Box::new(MemoryBackend::new()));
+    wal.append(&[write_entry(address, b"logged")]).unwrap();
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend_with_log(vol, wal.into_log()).unwrap();
+
+    assert!(read_bytes(&mut s, address, 6) == b"logged");
+}
+
+#[test]
+fn ignore_torn_transaction() {
+    let (vol, address) = volume_with_block();
+
+    let mut wal = Wal::new(Box::new(MemoryBackend::new()));
+    wal.append(&[write_entry(address, b"first")]).unwrap();
+    wal.append(&[write_entry(address, b"second")]).unwrap();
+
+    // transaksi terakhir hanya tertulis sebagian
+    let mut log = wal.into_log();
+    let len = log.len().unwrap();
+    log.set_len(len - 3).unwrap();
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend_with_log(vol, log).unwrap();
+
+    assert!(read_bytes(&mut s, address, 6) == b"first\0");
+}
+
+#[test]
+fn ignore_stale_transaction() {
+    let (vol, address) = volume_with_block();
+
+    // frame milik log sebelumnya (dengan salt yang berbeda) yang masih
+    // tersisa setelah frame yang baru
+    let mut old_wal = Wal::new(Box::new(MemoryBackend::new()));
+    old_wal.append(&[write_entry(address, b"old-0")]).unwrap();
+    old_wal.append(&[write_entry(address, b"old-1")]).unwrap();
+
+    // kedua frame memiliki ukuran yang sama, ambil frame yang kedua
+    let mut old_log = old_wal.into_log();
+    let frame_len = old_log.len().unwrap() / 2;
+
+    let mut stale = vec![0u8; frame_len as usize];
+    old_log.read_exact_at(frame_len, &mut stale).unwrap();
+
+    let mut wal = Wal::new(Box::new(MemoryBackend::new()));
+    wal.append(&[write_entry(address, b"new-0")]).unwrap();
+
+    let mut log = wal.into_log();
+    let end = log.len().unwrap();
+    log.write_all_at(end, &stale).unwrap();
+
+    let mut s: Storage<MemoryBackend> = Storage::default();
+    s.mount_backend_with_log(vol, log).unwrap();
+
+    assert!(read_bytes(&mut s, address, 5) == b"new-0");
+}
+
+#[test]
+#[serial]
+fn wal_emptied_after_operations() {
+    let p = path_of!("tmp/storage/wal.neondb");
+    util::ensure_not_exists(p);
+
+    let mut s = Storage::new();
+    s.mount_new(p, crate::NEONDB_FILE_DEFAULT_SIZE).unwrap();
+
+    let address = s.alloc(64).unwrap();
+    s.write(address, b"durable").unwrap();
+
+    assert!(mount::wal_path(p).metadata().unwrap().len() == 0);
+
+    // storage di-drop tanpa unmount, yang juga melepas lock dari volume
+    drop(s);
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount(p).unwrap();
+
+        let mut buff = [0u8; 7];
+        s.read(address, &mut buff).unwrap();
+
+        &buff == b"durable"
+    });
+}
+
+#[test]
+#[serial]
+fn stale_wal_discarded_on_new_volume() {
+    let p = path_of!("tmp/storage/wal_stale.neondb");
+    util::ensure_not_exists(p);
+
+    // log milik volume lama yang sudah dihapus
+    let mut wal = Wal::new(Box::new(mount::open_wal(p, true).unwrap()));
+    wal.append(&[Entry::SetLen(1)]).unwrap();
+
+    let mut s = Storage::new();
+    s.mount_new(p, crate::NEONDB_FILE_DEFAULT_SIZE).unwrap();
+    s.unmount().unwrap();
+
+    assert!({
+        let mut s = Storage::new();
+        s.mount(p).is_ok()
+    });
+}
+
+#[test]
+fn unapplied_transaction_kept_in_log() {
+    let fail_writes = Rc::new(Cell::new(false));
+    let vol = util::FaultyBackend {
+        inner: MemoryBackend::from_bytes(vec![0u8; 16]),
+        fail_writes: Rc::clone(&fail_writes),
+    };
+
+    let mut journal = Journal::new(vol, Some(Wal::new(Box::new(MemoryBackend::new()))));
+
+    journal.write_all_at(0, b"first").unwrap();
+    journal.commit(true).unwrap();
+
+    // transaksi sudah tercatat di log, namun gagal diterapkan ke volume
+    fail_writes.set(true);
+    journal.write_all_at(0, b"second").unwrap();
+
+    assert!(journal.commit(true).is_err() && journal.has_unapplied());
+
+    // log tidak boleh dikosongkan, meskipun volume kembali normal
+    fail_writes.set(false);
+
+    assert!({
+        journal.write_all_at(8, b"third").unwrap();
+
+        journal.commit(true).is_err() && journal.persist().is_err()
+    });
+
+    assert!({
+        let mut wal = journal.into_wal().unwrap();
+        let mut vol = MemoryBackend::from_bytes(vec![0u8; 16]);
+
+        wal.replay(&mut vol).unwrap() == 1 && &vol.as_bytes()[..6] == b"second"
+    });
+}
+
+#[test]
+fn unmount_after_unapplied_transaction() {
+    let (vol, address) = volume_with_block();
+
+    let fail_writes = Rc::new(Cell::new(false));
+    let vol = util::FaultyBackend {
+        inner: vol,
+        fail_writes: Rc::clone(&fail_writes),
+    };
+
+    let mut s = Storage::default();
+    s.mount_backend_with_log(vol, Box::new(MemoryBackend::new()))
+        .unwrap();
+
+    fail_writes.set(true);
+    assert!(s.write(address, b"lost").is_err());
+
+    fail_writes.set(false);
+    assert!(s.write(address, b"kept").is_err());
+
+    // volume tetap dapat dilepas tanpa mengubah volume maupun log
+    assert!(s.unmount().is_ok());
+}
+
+#[test]
+fn journal_overlapping_writes() {
+    let entries = [
+        write_entry(4, &[2u8; 8]),
+        write_entry(0, &[3u8; 6]),
+        write_entry(10, &[4u8; 4]),
+        write_entry(6, &[5u8; 2]),
+        Entry::SetLen(12),
+        write_entry(2, &[6u8; 12]),
+        Entry::SetLen(8),
+        Entry::SetLen(20),
+        write_entry(16, &[7u8; 2]),
+    ];
+
+    // hasil yang diharapkan adalah hasil penerapan seluruh entry secara
+    // berurutan ke backend
+    let mut expected = MemoryBackend::from_bytes(vec![1u8; 16]);
+    for entry in entries.iter() {
+        match entry {
+            Entry::Write { address, bytes } => expected.write_all_at(*address, bytes).unwrap(),
+            Entry::SetLen(len) => expected.set_len(*len).unwrap(),
+        }
+    }
+
+    let mut journal = Journal::new(MemoryBackend::from_bytes(vec![1u8; 16]), None);
+    for entry in entries.iter() {
+        match entry {
+            Entry::Write { address, bytes } => journal.write_all_at(*address, bytes).unwrap(),
+            Entry::SetLen(len) => journal.set_len(*len).unwrap(),
+        }
+    }
+
+    assert!({
+        let mut buff = [0u8; 20];
+        journal.read_exact_at(0, &mut buff).unwrap();
+
+        let mut part = [0u8; 5];
+        journal.read_exact_at(5, &mut part).unwrap();
+
+        buff == expected.as_bytes() && part == expected.as_bytes()[5..10]
+    });
+
+    assert!({
+        journal.commit(false).unwrap();
+
+        journal.backend().as_bytes() == expected.as_bytes()
+    });
+}
@@ -1,8 +1,15 @@
-use crate::{NEONDB_FILE_MARK, NEONDB_FILE_SIZE};
+use crate::alloc::AllocatorKind;
+use crate::superblock::Superblock;
+use crate::{
+    FileBackend, MemoryBackend, PathBackend, Storage, VolumeBackend, NEONDB_FILE_ALLOCATABLE_START,
+    NEONDB_FILE_DEFAULT_SIZE,
+};
 
+use std::cell::Cell;
 use std::fs::{self, OpenOptions};
-use std::io::{prelude::*, SeekFrom};
+use std::io;
 use std::path::Path;
+use std::rc::Rc;
 
 #[macro_export]
 macro_rules! path_of {
@@ -16,29 +23,90 @@ macro_rules! path_of {
 //
 // Gantikan fungsi ini
 pub fn fresh_volume(path: &Path) {
-    let mut vol = OpenOptions::new()
-        .read(true)
+    OpenOptions::new()
         .write(true)
         .create(true)
+        .truncate(false)
         .open(path)
         .unwrap();
 
-    if path.metadata().unwrap().len() != NEONDB_FILE_SIZE {
-        vol.set_len(NEONDB_FILE_SIZE).unwrap();
+    let mut vol = FileBackend::open(path).unwrap();
+
+    if vol.len().unwrap() != NEONDB_FILE_DEFAULT_SIZE {
+        vol.set_len(NEONDB_FILE_DEFAULT_SIZE).unwrap();
     }
 
-    // NEONDB_FILE_MARK + mark dari head-block milik RSSAlloc
-    let first_bytes = NEONDB_FILE_MARK
-        .as_bytes()
+    // menandai volume
+    Superblock::new(NEONDB_FILE_DEFAULT_SIZE, AllocatorKind::Rss)
+        .write_to(&mut vol)
+        .unwrap();
+
+    // mark dari head-block milik RSSAlloc
+    let head_bytes = 16u64
+        .to_be_bytes()
         .iter()
-        .chain(&16u64.to_be_bytes())
         .chain(&0u64.to_be_bytes())
-        .map(|b| *b)
+        .copied()
         .collect::<Vec<u8>>();
 
-    // menandai volume
-    vol.seek(SeekFrom::Start(0)).unwrap();
-    vol.write(&first_bytes).unwrap();
+    vol.write_all_at(NEONDB_FILE_ALLOCATABLE_START, &head_bytes)
+        .unwrap();
+}
+
+// Volume baru yang seluruhnya berada di memori, sehingga test yang
+// menggunakannya tidak perlu dijalankan secara serial.
+pub fn memory_storage() -> Storage<MemoryBackend> {
+    memory_storage_sized(NEONDB_FILE_DEFAULT_SIZE)
+}
+
+pub fn memory_storage_sized(size: u64) -> Storage<MemoryBackend> {
+    let mut s = Storage::default();
+    s.mount_memory(size).unwrap();
+
+    s
+}
+
+// Melepas lalu memasang kembali volume, layaknya ketika aplikasi
+// dijalankan ulang.
+pub fn remount(s: Storage<MemoryBackend>) -> Storage<MemoryBackend> {
+    let mut s = s;
+    let vol = s.unmount().unwrap();
+
+    let mut s = Storage::default();
+    s.mount_backend(vol).unwrap();
+
+    s
+}
+
+// Backend di memori yang penulisannya dapat dibuat gagal
+pub struct FaultyBackend {
+    pub inner: MemoryBackend,
+    pub fail_writes: Rc<Cell<bool>>,
+}
+
+impl VolumeBackend for FaultyBackend {
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+        self.inner.read_at(offset, buff)
+    }
+
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize> {
+        if self.fail_writes.get() {
+            return Err(io::Error::other("disk full"));
+        }
+        self.inner.write_at(offset, buff)
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        self.inner.len()
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        self.inner.set_len(len)
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.inner.sync()
+    }
 }
 
 pub fn ensure_not_exists(path: &Path) {
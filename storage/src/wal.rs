@@ -0,0 +1,598 @@
+use super::{ErrorKind, Result, VolumeBackend};
+
+use std::cmp;
+use std::collections::BTreeMap;
+use std::convert::TryInto;
+use std::io;
+
+// Penanda awal dari tiap frame ("NWAL")
+const FRAME_MAGIC: u32 = 0x4e57_414c;
+
+// magic + salt + nomor urut + panjang payload
+const FRAME_HEADER_SIZE: usize = 4 + 8 + 8 + 8;
+const FRAME_CHECKSUM_SIZE: usize = 4;
+
+const ENTRY_WRITE: u8 = 1;
+const ENTRY_SET_LEN: u8 = 2;
+
+/// Satu perubahan terhadap volume yang dicatat di dalam log.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Entry {
+    Write { address: u64, bytes: Vec<u8> },
+    SetLen(u64),
+}
+
+impl Entry {
+    fn apply<B: VolumeBackend>(&self, vol: &mut B) -> io::Result<()> {
+        match self {
+            Entry::Write { address, bytes } => vol.write_all_at(*address, bytes),
+            Entry::SetLen(len) => vol.set_len(*len),
+        }
+    }
+
+    fn encode(&self, buff: &mut Vec<u8>) {
+        match self {
+            Entry::Write { address, bytes } => {
+                buff.push(ENTRY_WRITE);
+                buff.extend_from_slice(&address.to_be_bytes());
+                buff.extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+                buff.extend_from_slice(bytes);
+            }
+            Entry::SetLen(len) => {
+                buff.push(ENTRY_SET_LEN);
+                buff.extend_from_slice(&len.to_be_bytes());
+            }
+        }
+    }
+
+    // Payload dari frame sudah lolos verifikasi checksum, sehingga
+    // kegagalan di sini berarti log ditulis oleh versi yang berbeda.
+    fn decode_all(mut bytes: &[u8]) -> Option<Vec<Entry>> {
+        let mut entries = vec![];
+
+        while !bytes.is_empty() {
+            let (kind, rest) = bytes.split_first()?;
+
+            match *kind {
+                ENTRY_WRITE => {
+                    let address = read_u64(rest.get(..8)?);
+                    let len: usize = read_u64(rest.get(8..16)?).try_into().ok()?;
+                    let data = rest.get(16..16 + len)?;
+
+                    entries.push(Entry::Write {
+                        address,
+                        bytes: data.to_vec(),
+                    });
+                    bytes = &rest[16 + len..];
+                }
+                ENTRY_SET_LEN => {
+                    entries.push(Entry::SetLen(read_u64(rest.get(..8)?)));
+                    bytes = &rest[8..];
+                }
+                _ => return None,
+            }
+        }
+
+        Some(entries)
+    }
+}
+
+/// Write-ahead log.
+///
+/// Setiap transaksi dicatat sebagai satu frame (berisi seluruh `Entry`
+/// dari transaksi tersebut beserta checksum-nya) di akhir log sebelum
+/// diterapkan ke volume. Log baru disinkronisasikan ke media penyimpanan
+/// melalui `sync`.
+///
+/// Ketika volume dimounting, frame-frame yang masih tersisa di log akan
+/// diterapkan ulang. Frame terakhir yang tidak utuh (misalnya karena
+/// crash ketika penulisan log) akan diabaikan.
+///
+/// Setiap kali log dikosongkan, salt dari log akan diganti. Dengan begitu,
+/// sisa frame lama yang (mungkin) masih berada di log tidak akan ikut
+/// diterapkan.
+pub struct Wal {
+    log: Box<dyn VolumeBackend>,
+    salt: u64,
+    next_seq: u64,
+    end: u64,
+}
+
+impl Wal {
+    pub fn new(log: Box<dyn VolumeBackend>) -> Wal {
+        Wal {
+            log,
+            salt: new_salt(),
+            next_seq: 0,
+            end: 0,
+        }
+    }
+
+    /// Menerapkan ulang seluruh frame yang utuh ke volume, lalu
+    /// mengosongkan log.
+    ///
+    /// Nilai yang dikembalikan adalah jumlah frame yang diterapkan.
+    pub fn replay<B: VolumeBackend>(&mut self, vol: &mut B) -> io::Result<usize> {
+        let frames = self.frames()?;
+        for entries in frames.iter() {
+            for entry in entries.iter() {
+                entry.apply(vol)?;
+            }
+        }
+
+        self.checkpoint(vol)?;
+        Ok(frames.len())
+    }
+
+    // Membaca seluruh frame yang utuh dari log, tanpa mengubah log
+    fn frames(&mut self) -> io::Result<Vec<Vec<Entry>>> {
+        let len: usize = self
+            .log
+            .len()?
+            .try_into()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "log too large"))?;
+
+        let mut bytes = vec![0u8; len];
+        self.log.read_exact_at(0, &mut bytes)?;
+
+        Ok(decode_frames(&bytes))
+    }
+
+    /// Mencatat satu transaksi ke log. Transaksi tersebut belum tentu
+    /// tersimpan secara permanen sebelum `sync` dipanggil.
+    pub fn append(&mut self, entries: &[Entry]) -> io::Result<()> {
+        let frame = encode_frame(self.salt, self.next_seq, entries);
+
+        self.log.write_all_at(self.end, &frame)?;
+
+        self.next_seq += 1;
+        self.end += frame.len() as u64;
+        Ok(())
+    }
+
+    /// Memastikan seluruh transaksi yang sudah dicatat tersimpan secara
+    /// permanen.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.log.sync()
+    }
+
+    /// Mengosongkan log, setelah seluruh perubahan di volume tersimpan
+    /// secara permanen.
+    pub fn checkpoint<B: VolumeBackend>(&mut self, vol: &mut B) -> io::Result<()> {
+        vol.sync()?;
+
+        self.log.set_len(0)?;
+        self.log.sync()?;
+
+        self.salt = new_salt();
+        self.next_seq = 0;
+        self.end = 0;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub fn into_log(self) -> Box<dyn VolumeBackend> {
+        self.log
+    }
+}
+
+/// Volume yang seluruh penulisannya dilakukan melalui transaksi.
+///
+/// Penulisan (termasuk perubahan ukuran) hanya dikumpulkan di memori,
+/// dan baru diterapkan ke backend ketika `commit` dipanggil. Jika WAL
+/// digunakan, setiap transaksi dicatat terlebih dulu di log.
+///
+/// Penulisan yang belum di-commit disimpan berdasarkan alamatnya, sehingga
+/// byte yang ditulis berulang kali hanya disimpan sekali, dan pembacaan
+/// tidak perlu memeriksa seluruh penulisan yang belum di-commit.
+///
+/// Journal yang read-only tidak pernah menerapkan penulisan ke backend,
+/// sehingga seluruh penulisan tetap ditahan di memori.
+///
+/// Jika transaksi yang sudah dicatat di log gagal diterapkan ke backend,
+/// log tidak akan pernah dikosongkan dan seluruh commit berikutnya akan
+/// gagal. Transaksi tersebut baru diterapkan ulang ketika volume
+/// dimounting kembali.
+pub struct Journal<B: VolumeBackend> {
+    backend: B,
+    wal: Option<Wal>,
+    read_only: bool,
+
+    // Segmen-segmen yang tidak saling tumpang tindih, berisi byte terbaru
+    // yang belum di-commit, dengan alamat awal segmen sebagai key
+    overlay: BTreeMap<u64, Vec<u8>>,
+
+    // Ukuran terkecil dan ukuran terakhir yang ditetapkan melalui set_len
+    // sejak commit sebelumnya. Byte di luar ukuran terkecil sudah pasti
+    // terhapus, meskipun volume kemudian diperbesar kembali.
+    truncated: Option<u64>,
+    pending_len: Option<u64>,
+
+    // Terdapat transaksi yang sudah diterapkan ke backend, namun belum
+    // tentu tersimpan secara permanen
+    dirty: bool,
+
+    // Terdapat transaksi di log yang gagal diterapkan ke backend, sehingga
+    // kondisi backend tidak lagi utuh
+    unapplied: bool,
+}
+
+impl<B: VolumeBackend> Journal<B> {
+    pub fn new(backend: B, wal: Option<Wal>) -> Journal<B> {
+        Journal {
+            backend,
+            wal,
+            read_only: false,
+            overlay: BTreeMap::new(),
+            truncated: None,
+            pending_len: None,
+            dirty: false,
+            unapplied: false,
+        }
+    }
+
+    pub fn new_read_only(backend: B) -> Journal<B> {
+        Journal {
+            read_only: true,
+            ..Journal::new(backend, None)
+        }
+    }
+
+    // Menerapkan sisa transaksi di log (jika ada) ke backend
+    pub fn recover(&mut self) -> Result<()> {
+        if let Some(wal) = self.wal.as_mut() {
+            wal.replay(&mut self.backend)?;
+        }
+        Ok(())
+    }
+
+    // Sama seperti recover, namun sisa transaksi di log hanya dicatat
+    // sebagai penulisan yang belum di-commit, sehingga backend maupun log
+    // sama sekali tidak diubah
+    pub fn recover_pending(&mut self, wal: &mut Wal) -> Result<()> {
+        for entries in wal.frames()?.iter() {
+            for entry in entries.iter() {
+                entry.apply(self)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Transaksi selalu dicatat di log sebelum diterapkan ke backend,
+    // sedangkan durable hanya menentukan apakah log maupun backend
+    // disinkronisasikan. Jika durable bernilai false, transaksi tersebut
+    // (beserta transaksi sebelumnya yang juga belum disimpan secara
+    // permanen) dapat hilang ketika terjadi crash, namun frame yang
+    // tersimpan utuh di log tetap diterapkan ulang ketika mounting.
+    pub fn commit(&mut self, durable: bool) -> Result<()> {
+        // penulisan terhadap journal yang read-only tetap ditahan di memori
+        if self.read_only {
+            return Ok(());
+        }
+
+        if self.unapplied {
+            self.take_entries();
+            return Err(unapplied_error().into());
+        }
+
+        if self.has_pending() {
+            let entries = self.take_entries();
+
+            if let Some(wal) = self.wal.as_mut() {
+                wal.append(&entries)?;
+
+                if durable {
+                    wal.sync()?;
+                }
+            }
+
+            self.dirty = true;
+
+            // frame yang sudah tercatat tidak boleh terhapus dari log,
+            // meskipun hanya sebagian transaksi yang berhasil diterapkan
+            if let Err(err) = entries.iter().try_for_each(|e| e.apply(&mut self.backend)) {
+                self.unapplied = self.wal.is_some();
+                return Err(err.into());
+            }
+        }
+
+        if durable {
+            self.persist()?;
+        }
+        Ok(())
+    }
+
+    // Memastikan seluruh transaksi yang sudah diterapkan ke backend
+    // tersimpan secara permanen, lalu mengosongkan log.
+    pub fn persist(&mut self) -> Result<()> {
+        if self.unapplied {
+            return Err(unapplied_error().into());
+        }
+        if !self.dirty {
+            return Ok(());
+        }
+
+        match self.wal.as_mut() {
+            Some(wal) => wal.checkpoint(&mut self.backend),
+            None => self.backend.sync(),
+        }?;
+
+        self.dirty = false;
+        Ok(())
+    }
+
+    // Nilai yang dikembalikan menandakan ada atau tidaknya penulisan
+    // yang dibuang.
+    pub fn rollback(&mut self) -> bool {
+        let has_pending = self.has_pending();
+        self.take_entries();
+
+        has_pending
+    }
+
+    fn has_pending(&self) -> bool {
+        !self.overlay.is_empty() || self.pending_len.is_some()
+    }
+
+    // Menyusun ulang perubahan yang belum di-commit menjadi daftar Entry.
+    // Perubahan ukuran diterapkan terlebih dulu, karena seluruh segmen
+    // yang tersisa ditulis setelah perubahan ukuran terakhir.
+    fn take_entries(&mut self) -> Vec<Entry> {
+        let mut entries = vec![];
+
+        if let Some(len) = self.truncated.take() {
+            entries.push(Entry::SetLen(len));
+        }
+        if let Some(len) = self.pending_len.take() {
+            if entries.last() != Some(&Entry::SetLen(len)) {
+                entries.push(Entry::SetLen(len));
+            }
+        }
+
+        entries.extend(
+            std::mem::take(&mut self.overlay)
+                .into_iter()
+                .map(|(address, bytes)| Entry::Write { address, bytes }),
+        );
+
+        entries
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub fn has_unapplied(&self) -> bool {
+        self.unapplied
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn into_backend(self) -> B {
+        self.backend
+    }
+
+    #[cfg(test)]
+    pub fn into_wal(self) -> Option<Wal> {
+        self.wal
+    }
+}
+
+impl<B: VolumeBackend> VolumeBackend for Journal<B> {
+    // Penulisan (maupun perubahan ukuran) yang belum di-commit ikut
+    // terlihat oleh pembacaan, seolah-olah sudah diterapkan ke backend.
+    fn read_at(&mut self, offset: u64, buff: &mut [u8]) -> io::Result<usize> {
+        if !self.has_pending() {
+            return self.backend.read_at(offset, buff);
+        }
+
+        let len = self.len()?;
+        if offset >= len {
+            return Ok(0);
+        }
+
+        let n = cmp::min(buff.len() as u64, len - offset) as usize;
+        let buff = &mut buff[..n];
+
+        // byte di luar ukuran backend dianggap bernilai 0
+        let mut read = 0;
+        while read < n {
+            match self
+                .backend
+                .read_at(offset + read as u64, &mut buff[read..])?
+            {
+                0 => break,
+                m => read += m,
+            }
+        }
+        buff[read..].fill(0);
+
+        let end = offset + n as u64;
+
+        if let Some(truncated) = self.truncated.filter(|len| *len < end) {
+            let start = cmp::max(truncated, offset);
+            buff[(start - offset) as usize..].fill(0);
+        }
+
+        // hanya segmen terakhir yang dimulai sebelum offset yang mungkin
+        // beririsan dengan awal buffer
+        let before = self.overlay.range(..offset).next_back();
+
+        for (address, bytes) in before.into_iter().chain(self.overlay.range(offset..end)) {
+            let start = cmp::max(*address, offset);
+            let stop = cmp::min(address + bytes.len() as u64, end);
+
+            if start < stop {
+                buff[(start - offset) as usize..(stop - offset) as usize]
+                    .copy_from_slice(&bytes[(start - address) as usize..(stop - address) as usize]);
+            }
+        }
+
+        Ok(n)
+    }
+
+    fn write_at(&mut self, offset: u64, buff: &[u8]) -> io::Result<usize> {
+        if buff.is_empty() {
+            return Ok(0);
+        }
+
+        let end = offset
+            .checked_add(buff.len() as u64)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "offset out of range"))?;
+
+        // segmen yang dimulai sebelum offset dipotong, namun bagian yang
+        // melewati akhir penulisan tetap dipertahankan
+        let mut tail = None;
+
+        if let Some((address, bytes)) = self.overlay.range_mut(..offset).next_back() {
+            let stop = address + bytes.len() as u64;
+
+            if stop > end {
+                tail = Some(bytes[(end - address) as usize..].to_vec());
+            }
+            if stop > offset {
+                bytes.truncate((offset - address) as usize);
+            }
+        }
+
+        // segmen yang dimulai di dalam rentang penulisan ditimpa seluruhnya,
+        // kecuali bagian yang melewati akhir penulisan
+        let covered: Vec<u64> = self.overlay.range(offset..end).map(|(a, _)| *a).collect();
+
+        for address in covered {
+            let bytes = self.overlay.remove(&address).unwrap();
+            let stop = address + bytes.len() as u64;
+
+            if stop > end {
+                tail = Some(bytes[(end - address) as usize..].to_vec());
+            }
+        }
+
+        if let Some(tail) = tail {
+            self.overlay.insert(end, tail);
+        }
+        self.overlay.insert(offset, buff.to_vec());
+
+        Ok(buff.len())
+    }
+
+    fn len(&self) -> io::Result<u64> {
+        match self.pending_len {
+            Some(len) => Ok(len),
+            None => self.backend.len(),
+        }
+    }
+
+    fn set_len(&mut self, len: u64) -> io::Result<()> {
+        // penulisan di luar ukuran yang baru ikut terhapus
+        self.overlay.split_off(&len);
+
+        if let Some((address, bytes)) = self.overlay.range_mut(..len).next_back() {
+            if address + bytes.len() as u64 > len {
+                bytes.truncate((len - address) as usize);
+            }
+        }
+
+        self.truncated = Some(self.truncated.map_or(len, |t| cmp::min(t, len)));
+        self.pending_len = Some(len);
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.backend.flush()
+    }
+
+    fn sync(&mut self) -> io::Result<()> {
+        self.commit(true).map_err(|err| match err.into_kind() {
+            ErrorKind::Io(err) => err,
+            kind => io::Error::other(kind.to_string()),
+        })
+    }
+}
+
+fn encode_frame(salt: u64, seq: u64, entries: &[Entry]) -> Vec<u8> {
+    let mut payload = vec![];
+    for entry in entries.iter() {
+        entry.encode(&mut payload);
+    }
+
+    let mut frame = Vec::with_capacity(FRAME_HEADER_SIZE + payload.len() + FRAME_CHECKSUM_SIZE);
+    frame.extend_from_slice(&FRAME_MAGIC.to_be_bytes());
+    frame.extend_from_slice(&salt.to_be_bytes());
+    frame.extend_from_slice(&seq.to_be_bytes());
+    frame.extend_from_slice(&(payload.len() as u64).to_be_bytes());
+    frame.extend_from_slice(&payload);
+
+    // checksum mencakup header, sehingga salt maupun nomor urut yang
+    // rusak juga akan terdeteksi
+    let checksum = crc32c::crc32c(&frame);
+    frame.extend_from_slice(&checksum.to_be_bytes());
+
+    frame
+}
+
+// Mengambil seluruh frame yang utuh secara berurutan, dimulai dari awal
+// log. Pembacaan berhenti pada frame pertama yang tidak valid.
+fn decode_frames(mut bytes: &[u8]) -> Vec<Vec<Entry>> {
+    let mut frames = vec![];
+    let mut salt = None;
+
+    while bytes.len() >= FRAME_HEADER_SIZE {
+        if read_u32(&bytes[..4]) != FRAME_MAGIC {
+            break;
+        }
+
+        let frame_salt = read_u64(&bytes[4..12]);
+        let seq = read_u64(&bytes[12..20]);
+
+        if *salt.get_or_insert(frame_salt) != frame_salt || seq != frames.len() as u64 {
+            break;
+        }
+
+        let payload_len: usize = match read_u64(&bytes[20..28]).try_into() {
+            Ok(len) => len,
+            Err(_) => break,
+        };
+
+        let frame_len = match FRAME_HEADER_SIZE
+            .checked_add(payload_len)
+            .and_then(|len| len.checked_add(FRAME_CHECKSUM_SIZE))
+        {
+            Some(len) if len <= bytes.len() => len,
+            _ => break,
+        };
+
+        let body = &bytes[..frame_len - FRAME_CHECKSUM_SIZE];
+        if crc32c::crc32c(body) != read_u32(&bytes[frame_len - FRAME_CHECKSUM_SIZE..frame_len]) {
+            break;
+        }
+
+        match Entry::decode_all(&body[FRAME_HEADER_SIZE..]) {
+            Some(entries) => frames.push(entries),
+            None => break,
+        }
+
+        bytes = &bytes[frame_len..];
+    }
+
+    frames
+}
+
+fn unapplied_error() -> io::Error {
+    io::Error::other("logged transaction could not be applied, remount the volume to replay it")
+}
+
+fn new_salt() -> u64 {
+    let (high, _) = uuid::Uuid::new_v4().as_u64_pair();
+    high
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes(bytes.try_into().unwrap())
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_be_bytes(bytes.try_into().unwrap())
+}